@@ -6,7 +6,7 @@ use tauri::tray::TrayIconBuilder;
 use once_cell::sync::Lazy;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::Duration;
 
@@ -19,29 +19,82 @@ use windows::Win32::UI::WindowsAndMessaging::{
     AllowSetForegroundWindow, GetForegroundWindow, GetWindowRect, GetWindowLongPtrW, GWL_STYLE, WS_POPUP, WS_CAPTION,
 };
 #[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{GetClassNameW, GetWindowTextW, GetWindowThreadProcessId};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{EVENT_SYSTEM_FOREGROUND, WINEVENT_OUTOFCONTEXT, WINEVENT_SKIPOWNPROCESS};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Accessibility::{SetWinEventHook, HWINEVENTHOOK};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{SetWindowPos, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    SystemParametersInfoW, SPIF_SENDCHANGE, SPIF_UPDATEINIFILE, SPI_SETCURSORS,
+};
+#[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::{ASFW_ANY, SW_SHOWNORMAL};
 #[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetSystemMetrics, SM_CONVERTIBLESLATEMODE, SM_MAXIMUMTOUCHES, WM_DISPLAYCHANGE, WM_DPICHANGED, WM_SETTINGCHANGE,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{GetCursorPos, SM_CXSCREEN};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Shell::{
+    SHAppBarMessage, ABE_LEFT, ABE_RIGHT, ABE_TOP, ABM_GETSTATE, ABM_GETTASKBARPOS, ABS_AUTOHIDE, APPBARDATA,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+#[cfg(target_os = "windows")]
 use windows::Media::Control::{
     GlobalSystemMediaTransportControlsSessionManager,
     GlobalSystemMediaTransportControlsSession,
     GlobalSystemMediaTransportControlsSessionPlaybackStatus,
     GlobalSystemMediaTransportControlsSessionMediaProperties,
+    CurrentSessionChangedEventArgs, MediaPropertiesChangedEventArgs, PlaybackInfoChangedEventArgs,
+};
+#[cfg(target_os = "windows")]
+use windows::Media::{MediaPlaybackAutoRepeatMode, MediaPlaybackType};
+#[cfg(target_os = "windows")]
+use windows::Graphics::Imaging::{
+    BitmapAlphaMode, BitmapDecoder, BitmapEncoder, BitmapPixelFormat, BitmapTransform, ColorManagementMode,
+    ExifOrientationMode,
+};
+#[cfg(target_os = "windows")]
+use windows::Storage::Streams::{
+    DataReader, IRandomAccessStream, IRandomAccessStreamWithContentType, InMemoryRandomAccessStream,
 };
 #[cfg(target_os = "windows")]
 use windows::Foundation::AsyncStatus;
 #[cfg(target_os = "windows")]
+use windows::Devices::Radios::{Radio, RadioKind, RadioState};
+#[cfg(target_os = "windows")]
 use windows::Win32::Media::Audio::{
-    eRender, eConsole, eMultimedia,
-    Endpoints::IAudioEndpointVolume,
+    eRender, eCapture, eAll, eConsole, eMultimedia, eCommunications, EDataFlow, ERole,
+    Endpoints::{IAudioEndpointVolume, IAudioMeterInformation},
     IMMDeviceEnumerator, IMMDevice, IMMDeviceCollection, MMDeviceEnumerator,
     IAudioSessionManager2, IAudioSessionEnumerator, IAudioSessionControl, IAudioSessionControl2,
     ISimpleAudioVolume, AudioSessionState,
-    DEVICE_STATE_ACTIVE,
+    DEVICE_STATE_ACTIVE, DEVICE_STATE_DISABLED, DEVICE_STATE_UNPLUGGED,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::Media::Audio::{IMMNotificationClient, IMMNotificationClient_Impl, DEVICE_STATE};
+#[cfg(target_os = "windows")]
+use windows::Win32::Media::Audio::{
+    AudioSessionDisconnectReason, AudioSessionStateExpired, IAudioSessionEvents, IAudioSessionEvents_Impl, IAudioSessionNotification,
+    IAudioSessionNotification_Impl,
 };
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ};
 #[cfg(target_os = "windows")]
-use windows::Win32::System::Com::StructuredStorage::PropVariantToStringAlloc;
+use windows::Win32::System::Com::{CoIncrementMTAUsage, CoUninitialize};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Com::StructuredStorage::{PropVariantToStringAlloc, PropVariantToUInt32};
+#[cfg(target_os = "windows")]
+use windows::Win32::Media::Audio::PKEY_AudioEndpoint_FormFactor;
+#[cfg(target_os = "windows")]
+use windows::Win32::Devices::FunctionDiscovery::PKEY_DeviceClass_IconPath;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
 #[cfg(target_os = "windows")]
@@ -51,23 +104,154 @@ use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
 #[cfg(target_os = "windows")]
 use windows::Win32::Devices::Display::{
     GetNumberOfPhysicalMonitorsFromHMONITOR, GetPhysicalMonitorsFromHMONITOR,
-    GetMonitorBrightness, SetMonitorBrightness, DestroyPhysicalMonitor,
+    GetMonitorBrightness, SetMonitorBrightness, DestroyPhysicalMonitors,
+    GetMonitorContrast, SetMonitorContrast,
+    GetVCPFeatureAndVCPFeatureReply, SetVCPFeature, MC_MOMENTARY,
     PHYSICAL_MONITOR,
 };
 #[cfg(target_os = "windows")]
-use windows::Win32::Graphics::Gdi::{MonitorFromWindow, MONITOR_DEFAULTTOPRIMARY};
+use windows::Win32::Graphics::Gdi::{EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::ColorSystem::SetDeviceGammaRamp;
+#[cfg(target_os = "windows")]
+use windows::Win32::Devices::Display::{
+    GetDisplayConfigBufferSizes, QueryDisplayConfig, DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO,
+    DISPLAYCONFIG_TOPOLOGY_CLONE, DISPLAYCONFIG_TOPOLOGY_EXTEND, DISPLAYCONFIG_TOPOLOGY_EXTERNAL,
+    DISPLAYCONFIG_TOPOLOGY_ID, DISPLAYCONFIG_TOPOLOGY_INTERNAL, QDC_DATABASE_CURRENT,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::{MonitorFromWindow, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTOPRIMARY};
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::{GetDC, GetPixel, ReleaseDC, CLR_INVALID};
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::{GetDeviceCaps, LOGPIXELSX};
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::{CombineRgn, CreateRectRgn, SetWindowRgn, RGN_OR};
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Gdi::{
+    DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON};
+#[cfg(target_os = "windows")]
+use windows::Win32::Graphics::Dwm::{
+    DwmGetWindowAttribute, DWMWA_CAPTION_COLOR, DWMWA_COLOR_DEFAULT, DWMWA_COLOR_NONE,
+    DWMWA_EXTENDED_FRAME_BOUNDS,
+};
 #[cfg(target_os = "windows")]
 use windows::core::{HSTRING, Interface};
 #[cfg(target_os = "windows")]
-use windows::Foundation::TypedEventHandler;
+use windows::Foundation::{EventRegistrationToken, TypedEventHandler};
 #[cfg(target_os = "windows")]
 use windows::UI::Notifications::Management::{UserNotificationListener, UserNotificationListenerAccessStatus};
 #[cfg(target_os = "windows")]
-use windows::UI::Notifications::{UserNotification, UserNotificationChangedEventArgs, UserNotificationChangedKind};
+use windows::UI::Notifications::{UserNotification, UserNotificationChangedEventArgs};
+
+#[cfg(target_os = "windows")]
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::Storage::FileSystem::{GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_CANCELLED, HANDLE};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_MORE_DATA, ERROR_NO_MORE_ITEMS, WIN32_ERROR};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::ERROR_INSUFFICIENT_BUFFER;
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegCreateKeyExW, RegEnumKeyExW, RegEnumValueW, RegGetValueW, RegOpenKeyExW, RegSetValueExW,
+    HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, KEY_SET_VALUE, REG_BINARY, REG_DWORD, REG_OPTION_NON_VOLATILE,
+    REG_SAM_FLAGS, RRF_RT_REG_BINARY, RRF_RT_REG_DWORD, RRF_RT_REG_QWORD,
+};
+
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Power::{
+    PowerDeterminePlatformRoleEx, PlatformRoleMobile, PlatformRoleSlate, POWER_PLATFORM_ROLE_V2,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::System::Power::{
+    PowerEnumerate, PowerGetActiveScheme, PowerReadFriendlyName, PowerSetActiveScheme, ACCESS_SCHEME,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::{LocalFree, HLOCAL};
+#[cfg(target_os = "windows")]
+use windows::core::GUID;
 
 #[cfg(target_os = "windows")]
 use brightness::blocking::Brightness;
+#[cfg(target_os = "windows")]
+use base64::Engine;
+
+
+// =============================================================================
+// Window Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayMonitor {
+    pub name: String,       // OS monitor name, e.g. "\\.\DISPLAY2"
+    pub x: i32,             // Physical pixels, virtual-desktop coordinates
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+/// Usable area of a monitor (taskbar and docked app bars excluded)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkArea {
+    pub monitor: String,    // Same name as DisplayMonitor.name
+    pub x: f64,             // Logical pixels, virtual-desktop coordinates
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub is_primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskbarInfo {
+    pub edge: String,       // "top" | "bottom" | "left" | "right"
+    pub auto_hide: bool,
+    pub x: i32,             // Physical pixels; while auto-hidden this is the docked rect
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Interactive rectangle for set_hit_test_region, in logical pixels relative to the window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HitTestRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForegroundApp {
+    pub title: String,
+    pub exe: String,            // e.g. "chrome.exe"; empty for elevated processes
+    pub process_id: u32,
+}
 
+/// Where the user left the island, restored on the next launch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedWindowPosition {
+    pub x: i32,             // Physical pixels, horizontal center of the island
+    pub y: i32,             // Physical pixels, top edge
+    pub monitor: String,    // Monitor name the island was on
+}
 
 // =============================================================================
 // Media Session Types
@@ -75,11 +259,46 @@ use brightness::blocking::Brightness;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaInfo {
+    pub session_id: String,      // Source app AUMID, used to target a specific session
     pub title: String,
     pub artist: String,
     pub album: Option<String>,
     pub is_playing: bool,
     pub app_name: Option<String>,
+    pub position_ms: u64,        // Playhead at last_updated_ms
+    pub duration_ms: u64,        // 0 for live/streaming content without an end time
+    pub last_updated_ms: u64,    // Unix ms when the app last reported its position
+    pub shuffle: Option<bool>,   // None when the player doesn't report it
+    pub repeat_mode: Option<String>, // "none" | "track" | "list"
+    pub playback_rate: f64,      // 1.0 = normal speed; advance the scrubber by this factor
+    pub controls: MediaControls,
+    pub genres: Vec<String>,
+    pub track_number: Option<i32>,
+    pub album_track_count: Option<i32>,
+    pub content_type: String,    // "music" | "video" | "image" | "unknown"
+}
+
+/// Transport controls the session's app currently accepts (gray out the rest)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaControls {
+    pub play_pause: bool,
+    pub play: bool,
+    pub pause: bool,
+    pub stop: bool,
+    pub next: bool,
+    pub previous: bool,
+    pub fast_forward: bool,
+    pub rewind: bool,
+    pub shuffle: bool,
+    pub repeat: bool,
+    pub seek: bool,              // TryChangePlaybackPositionAsync accepted
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaTheme {
+    pub accent_color: String,    // "#RRGGBB" dominant color of the artwork
+    pub is_video: bool,
+    pub is_dark_art: bool,       // average artwork luminance below 50%
 }
 
 // =============================================================================
@@ -90,6 +309,14 @@ pub struct MediaInfo {
 pub struct VolumeInfo {
     pub level: u32,      // 0-100
     pub is_muted: bool,
+    pub device_name: String, // Endpoint the level applies to, e.g. "Speakers (Realtek Audio)"
+    pub device_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeBalance {
+    pub left: f32,       // 0.0 - 1.0
+    pub right: f32,      // 0.0 - 1.0
 }
 
 // =============================================================================
@@ -101,6 +328,9 @@ pub struct AudioDevice {
     pub id: String,
     pub name: String,
     pub is_default: bool,
+    pub form_factor: String,       // "Speakers" | "Headphones" | "DigitalAudioDisplayDevice" | ... | "Unknown"
+    pub icon_path: Option<String>, // Shell icon resource, e.g. "%windir%\system32\mmres.dll,-3010"
+    pub state: String,             // "active" | "disabled" | "unplugged" | "notpresent"
 }
 
 // =============================================================================
@@ -109,12 +339,23 @@ pub struct AudioDevice {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioSession {
-    pub session_id: String,      // Unique session identifier
+    pub session_id: String,      // Session instance identifier (unique even within one process)
     pub app_name: String,        // Display name of the app
     pub process_id: u32,         // Windows process ID
     pub volume: f32,             // 0.0 - 1.0
     pub is_muted: bool,
     pub is_active: bool,         // Whether session is currently playing audio
+    pub state: String,           // "active" | "inactive" | "expired"
+    pub peak: f32,               // Current output peak 0.0 - 1.0 (poll get_session_peaks for meters)
+}
+
+/// Payload of "audio-session-updated" (a session's volume or mute changed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioSessionUpdate {
+    pub session_id: String,      // Session instance identifier (AudioSession.session_id)
+    pub process_id: u32,
+    pub volume: f32,             // 0.0 - 1.0
+    pub is_muted: bool,
 }
 
 // =============================================================================
@@ -129,18 +370,117 @@ pub struct SystemNotification {
     pub body: String,
     pub timestamp: u64,          // Unix timestamp in milliseconds
     pub aumid: Option<String>,   // App User Model ID for activation after Windows dismissal
+    pub app_logo: Option<String>, // Data URL of the app's tile logo
 }
 
 // =============================================================================
 // Battery Types
 // =============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BatteryInfo {
     pub percent: u32,           // 0-100
     pub is_charging: bool,
     pub is_battery_saver: bool,
     pub has_battery: bool,      // false on desktops without a battery
+    pub time_remaining_minutes: Option<u32>, // None while charging/on AC or still estimating
+    pub power_source: String,   // "ac" | "battery" | "unknown"
+}
+
+// =============================================================================
+// Quick Settings Types
+// =============================================================================
+
+/// Snapshot of the Windows Quick Settings flyout. Fields that couldn't be read are None.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickSettings {
+    pub wifi_enabled: Option<bool>,
+    pub bluetooth_enabled: Option<bool>,
+    pub airplane_mode: Option<bool>,
+    pub night_light: Option<bool>,
+    pub focus_assist: Option<bool>,     // Do not disturb / Focus assist suppressing toasts
+    pub battery_saver: Option<bool>,    // None on desktops without a battery
+    pub volume: Option<VolumeInfo>,
+    pub brightness: Option<BrightnessInfo>,
+}
+
+// =============================================================================
+// Startup App Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupApp {
+    pub name: String,            // Registry value name under the Run key
+    pub command: String,         // Command line that Windows launches at sign-in
+    pub enabled: bool,           // From StartupApproved (missing entry = enabled)
+    pub source: String,          // "HKCU" | "HKLM" | "HKLM32"
+    pub read_only: bool,         // true for machine-wide entries when not elevated
+}
+
+// =============================================================================
+// Power Plan Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerPlan {
+    pub guid: String,            // Scheme GUID, e.g. "381b4222-f694-41f0-9685-ff5bb260df2e"
+    pub name: String,            // Localized friendly name ("Balanced", "High performance", ...)
+    pub is_active: bool,
+}
+
+// =============================================================================
+// System Stats Types
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemStats {
+    pub cpu_percent: f32,        // 0-100, averaged over all cores since the previous sample
+    pub mem_used_mb: u64,
+    pub mem_total_mb: u64,
+}
+
+// =============================================================================
+// Network Types
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStatus {
+    pub is_connected: bool,      // Internet reachable (IPv4 or IPv6)
+    pub connection_type: String, // "wifi" | "ethernet" | "none" (wired, cellular and VPN-only all report "ethernet")
+    pub ssid: Option<String>,    // Only for "wifi"
+    pub signal_quality: Option<u32>, // 0-100, only for "wifi"
+}
+
+// =============================================================================
+// Bluetooth Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BluetoothDeviceInfo {
+    pub id: String,              // Association endpoint id
+    pub name: String,
+    pub connected: bool,
+    pub battery: Option<u32>,    // 0-100, None when disconnected or the device doesn't report it
+}
+
+// =============================================================================
+// Privacy Indicator Types
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivacyIndicators {
+    pub mic_in_use: bool,
+    pub camera_in_use: bool,
+}
+
+// =============================================================================
+// Clipboard Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardEntry {
+    pub text: String,
+    pub timestamp: u64,          // Unix timestamp in milliseconds
 }
 
 // =============================================================================
@@ -457,6 +797,42 @@ async fn prism_chat(request: PrismChatRequest) -> Result<PrismChatResponse, Stri
     Ok(PrismChatResponse { reply, actions, usage })
 }
 
+// =============================================================================
+// Config Files - small JSON state kept in the app config dir
+// =============================================================================
+
+fn config_file_path(app: &tauri::AppHandle, file: &str) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(file))
+        .map_err(|e| format!("Failed to resolve config dir: {}", e))
+}
+
+/// Read and parse a config file. None if it's missing or malformed.
+fn read_config_file<T: serde::de::DeserializeOwned>(app: &tauri::AppHandle, file: &str) -> Option<T> {
+    let path = config_file_path(app, file).ok()?;
+    let contents = std::fs::read_to_string(&path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("[PILLAR] Ignoring malformed {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn write_config_file<T: Serialize>(app: &tauri::AppHandle, file: &str, value: &T) -> Result<(), String> {
+    let path = config_file_path(app, file)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let json = serde_json::to_string(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", file, e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to save {}: {}", file, e))
+}
+
 // =============================================================================
 // Async Helpers - Poll Windows IAsyncOperation until complete
 // =============================================================================
@@ -466,8 +842,43 @@ async fn prism_chat(request: PrismChatRequest) -> Result<PrismChatResponse, Stri
 const POLL_MAX_ITERS: usize = 30;
 const POLL_SLEEP_MS: u64 = 5;
 
+/// Session manager from the first successful RequestAsync. RequestAsync costs a poll loop on
+/// every call, which made rapid transport taps sluggish.
+#[cfg(target_os = "windows")]
+static SESSION_MANAGER: Lazy<std::sync::Mutex<Option<GlobalSystemMediaTransportControlsSessionManager>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Get the (cached) SMTC session manager
 #[cfg(target_os = "windows")]
 fn poll_session_manager() -> Result<GlobalSystemMediaTransportControlsSessionManager, String> {
+    let mut cached = SESSION_MANAGER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(manager) = cached.as_ref() {
+        return Ok(manager.clone());
+    }
+
+    let manager = request_session_manager()?;
+    *cached = Some(manager.clone());
+    Ok(manager)
+}
+
+/// Drop the cached session manager after a failed HRESULT so the next call re-requests it.
+/// A null result (no session) is not a failure and keeps the cache.
+#[cfg(target_os = "windows")]
+fn invalidate_session_manager(error: &windows::core::Error) {
+    if error.code().is_err() {
+        *SESSION_MANAGER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// RequestAsync round-trips (each one a poll loop), counted so tests can check the cache
+#[cfg(all(test, target_os = "windows"))]
+static SESSION_MANAGER_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(target_os = "windows")]
+fn request_session_manager() -> Result<GlobalSystemMediaTransportControlsSessionManager, String> {
+    #[cfg(test)]
+    SESSION_MANAGER_REQUESTS.fetch_add(1, Ordering::SeqCst);
+
     let op = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()
         .map_err(|e| format!("Failed to request session manager: {}", e))?;
 
@@ -519,6 +930,57 @@ fn poll_bool_op(op: windows::Foundation::IAsyncOperation<bool>) -> Result<bool,
     Err("Timeout waiting for operation".to_string())
 }
 
+/// Generic version of the helpers above for any IAsyncOperation<T>.
+/// `what` only feeds the timeout error message.
+#[cfg(target_os = "windows")]
+fn poll_async_op<T: windows::core::RuntimeType + 'static>(
+    op: windows::Foundation::IAsyncOperation<T>,
+    what: &str,
+) -> Result<T, String> {
+    poll_async_op_with_limit(op, what, POLL_MAX_ITERS)
+}
+
+/// poll_async_op for operations that legitimately take longer than POLL_MAX_ITERS allows
+/// (device enumeration, radio round-trips). Only call off the main thread.
+#[cfg(target_os = "windows")]
+fn poll_async_op_with_limit<T: windows::core::RuntimeType + 'static>(
+    op: windows::Foundation::IAsyncOperation<T>,
+    what: &str,
+    max_iters: usize,
+) -> Result<T, String> {
+    for _ in 0..max_iters {
+        let status = op.Status().map_err(|e| format!("Failed to get status: {}", e))?;
+        if status == AsyncStatus::Completed {
+            return op.GetResults().map_err(|e| format!("Failed to get results: {}", e));
+        }
+        if status == AsyncStatus::Error {
+            return Err("Async operation failed".to_string());
+        }
+        thread::sleep(Duration::from_millis(POLL_SLEEP_MS));
+    }
+    Err(format!("Timeout waiting for {}", what))
+}
+
+/// Same as poll_async_op_with_limit for an IAsyncAction (no result)
+#[cfg(target_os = "windows")]
+fn poll_async_action(
+    action: windows::Foundation::IAsyncAction,
+    what: &str,
+    max_iters: usize,
+) -> Result<(), String> {
+    for _ in 0..max_iters {
+        let status = action.Status().map_err(|e| format!("Failed to get status: {}", e))?;
+        if status == AsyncStatus::Completed {
+            return action.GetResults().map_err(|e| format!("Failed to get results: {}", e));
+        }
+        if status == AsyncStatus::Error {
+            return Err("Async operation failed".to_string());
+        }
+        thread::sleep(Duration::from_millis(POLL_SLEEP_MS));
+    }
+    Err(format!("Timeout waiting for {}", what))
+}
+
 /// Set click-through mode for the window
 /// When enabled, mouse events pass through the window to apps behind it
 #[cfg(desktop)]
@@ -535,69 +997,551 @@ fn set_click_through(_window: tauri::Window, _ignore: bool) -> Result<(), String
     Err("Click-through not supported on mobile".to_string())
 }
 
-/// Resize window to specified dimensions
+/// Limit the clickable part of the window to `rects`; clicks anywhere else fall through to
+/// the apps behind. Implemented as a window region, so anything drawn outside the rects is
+/// clipped too: update the rects before the island grows. An empty list restores the full window.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_hit_test_region(window: tauri::Window, rects: Vec<HitTestRect>) -> Result<(), String> {
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let hwnd = HWND(hwnd.0);
+
+    if rects.is_empty() {
+        if unsafe { SetWindowRgn(hwnd, None, true) } == 0 {
+            return Err("Failed to clear window region".to_string());
+        }
+        return Ok(());
+    }
+
+    let scale_factor = window
+        .scale_factor()
+        .map_err(|e| format!("Failed to get scale factor: {}", e))?;
+
+    unsafe {
+        let region = CreateRectRgn(0, 0, 0, 0);
+        if region.is_invalid() {
+            return Err("Failed to create window region".to_string());
+        }
+        for rect in &rects {
+            let part = CreateRectRgn(
+                (rect.x * scale_factor).round() as i32,
+                (rect.y * scale_factor).round() as i32,
+                ((rect.x + rect.width) * scale_factor).round() as i32,
+                ((rect.y + rect.height) * scale_factor).round() as i32,
+            );
+            if part.is_invalid() {
+                let _ = DeleteObject(region);
+                return Err("Failed to create window region".to_string());
+            }
+            CombineRgn(region, region, part, RGN_OR);
+            let _ = DeleteObject(part);
+        }
+
+        // On success the system owns the region; only free it if it was rejected
+        if SetWindowRgn(hwnd, region, true) == 0 {
+            let _ = DeleteObject(region);
+            return Err("Failed to set window region".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_hit_test_region(_window: tauri::Window, _rects: Vec<HitTestRect>) -> Result<(), String> {
+    Err("Hit-test regions not supported on this platform".to_string())
+}
+
+/// Show or hide the island (e.g. auto-hide around fullscreen apps)
 #[cfg(desktop)]
 #[tauri::command]
-fn resize_window(window: tauri::Window, width: f64, height: f64) -> Result<(), String> {
-    if width <= 0.0 || height <= 0.0 {
-        return Err("Invalid dimensions".to_string());
+fn set_window_visible(window: tauri::Window, visible: bool) -> Result<(), String> {
+    if visible {
+        window.show().map_err(|e| format!("Failed to show window: {}", e))
+    } else {
+        window.hide().map_err(|e| format!("Failed to hide window: {}", e))
     }
-    window
-        .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
-        .map_err(|e| format!("Failed to resize: {}", e))
 }
 
 #[cfg(not(desktop))]
 #[tauri::command]
-fn resize_window(_window: tauri::Window, _width: f64, _height: f64) -> Result<(), String> {
-    Err("Window resize not supported on mobile".to_string())
+fn set_window_visible(_window: tauri::Window, _visible: bool) -> Result<(), String> {
+    Err("Window visibility not supported on mobile".to_string())
 }
 
-/// Position window at top-center of primary monitor
+/// Check whether the island is currently shown
 #[cfg(desktop)]
 #[tauri::command]
-fn position_window(window: tauri::Window) -> Result<(), String> {
-    let monitor = window
-        .primary_monitor()
-        .map_err(|e| format!("Failed to get monitor: {}", e))?
-        .ok_or_else(|| "No primary monitor found".to_string())?;
-    
-    let monitor_size = monitor.size();
-    let scale_factor = monitor.scale_factor();
-    let window_size = window
-        .outer_size()
-        .map_err(|e| format!("Failed to get window size: {}", e))?;
-    
-    let w = window_size.width as f64 / scale_factor;
-    let x = (monitor_size.width as f64 / scale_factor) / 2.0 - w / 2.0;
-    
+fn is_window_visible(window: tauri::Window) -> Result<bool, String> {
     window
-        .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y: 0.0 }))
-        .map_err(|e| format!("Failed to position: {}", e))
+        .is_visible()
+        .map_err(|e| format!("Failed to get window visibility: {}", e))
 }
 
 #[cfg(not(desktop))]
 #[tauri::command]
-fn position_window(_window: tauri::Window) -> Result<(), String> {
-    Err("Window positioning not supported on mobile".to_string())
+fn is_window_visible(_window: tauri::Window) -> Result<bool, String> {
+    Ok(true)
 }
 
-/// Check if the foreground window is "content" fullscreen (video/game), not just window fullscreen.
-/// We want: YouTube/Netflix video fullscreen, games → true.
-/// We don't want: browser F11 fullscreen, any app maximized/fullscreen → false.
-/// Uses window style: WS_POPUP or borderless (no caption) = content fullscreen; normal caption = window fullscreen.
-#[cfg(target_os = "windows")]
+/// Keep the island above other windows, including borderless fullscreen players.
+/// Never activates the island, so the foreground app keeps focus.
+#[cfg(desktop)]
 #[tauri::command]
-fn is_foreground_fullscreen(window: tauri::Window) -> Result<bool, String> {
-    // Get monitor info, return false if unavailable (safe default)
-    let monitor = match window.primary_monitor() {
-        Ok(Some(m)) => m,
-        _ => return Ok(false),
-    };
-
-    let mon_size = monitor.size();
-    let mon_w = mon_size.width as i32;
-    let mon_h = mon_size.height as i32;
+fn set_always_on_top(window: tauri::Window, enabled: bool) -> Result<(), String> {
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| format!("Failed to set always on top: {}", e))?;
+
+    // Re-insert at the top of the topmost band, above any topmost window that got there first
+    #[cfg(target_os = "windows")]
+    if enabled {
+        let hwnd = window
+            .hwnd()
+            .map_err(|e| format!("Failed to get window handle: {}", e))?;
+        unsafe {
+            SetWindowPos(
+                HWND(hwnd.0),
+                HWND_TOPMOST,
+                0,
+                0,
+                0,
+                0,
+                SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+            )
+        }
+        .map_err(|e| format!("Failed to raise window: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn set_always_on_top(_window: tauri::Window, _enabled: bool) -> Result<(), String> {
+    Err("Always on top not supported on mobile".to_string())
+}
+
+/// Set the island's z-order: "normal" | "topmost"
+#[cfg(desktop)]
+#[tauri::command]
+fn set_window_z_order(window: tauri::Window, order: String) -> Result<(), String> {
+    match order.as_str() {
+        "topmost" => set_always_on_top(window, true),
+        "normal" => set_always_on_top(window, false),
+        other => Err(format!("Unknown z-order: {}", other)),
+    }
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn set_window_z_order(_window: tauri::Window, _order: String) -> Result<(), String> {
+    Err("Window z-order not supported on mobile".to_string())
+}
+
+/// Resize window to specified dimensions
+#[cfg(desktop)]
+#[tauri::command]
+fn resize_window(window: tauri::Window, width: f64, height: f64) -> Result<(), String> {
+    if width <= 0.0 || height <= 0.0 {
+        return Err("Invalid dimensions".to_string());
+    }
+    window
+        .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
+        .map_err(|e| format!("Failed to resize: {}", e))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn resize_window(_window: tauri::Window, _width: f64, _height: f64) -> Result<(), String> {
+    Err("Window resize not supported on mobile".to_string())
+}
+
+/// Position window at its saved spot, or top-center of primary monitor when none is saved
+#[cfg(desktop)]
+#[tauri::command]
+fn position_window(window: tauri::Window) -> Result<(), String> {
+    if let Some((_, saved)) = saved_window_placement(window.available_monitors()) {
+        let window_size = window
+            .outer_size()
+            .map_err(|e| format!("Failed to get window size: {}", e))?;
+        return window
+            .set_position(tauri::Position::Physical(saved_top_left(&saved, window_size.width as i32)))
+            .map_err(|e| format!("Failed to position: {}", e));
+    }
+
+    let monitor = window
+        .primary_monitor()
+        .map_err(|e| format!("Failed to get monitor: {}", e))?
+        .ok_or_else(|| "No primary monitor found".to_string())?;
+    
+    let monitor_size = monitor.size();
+    let scale_factor = monitor.scale_factor();
+    let window_size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    
+    let w = window_size.width as f64 / scale_factor;
+    let x = (monitor_size.width as f64 / scale_factor) / 2.0 - w / 2.0;
+    
+    window
+        .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y: 0.0 }))
+        .map_err(|e| format!("Failed to position: {}", e))
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn position_window(_window: tauri::Window) -> Result<(), String> {
+    Err("Window positioning not supported on mobile".to_string())
+}
+
+/// Re-run resize_and_center's placement for the main window: top-center of the monitor the
+/// island is on, or the saved spot when that is on the same monitor.
+/// Returns that monitor's scale factor.
+#[cfg(target_os = "windows")]
+fn recenter_main_window(app: &tauri::AppHandle) -> Result<f64, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let window_size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+
+    // Same lookup as island_monitor, which takes a tauri::Window
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+        .ok_or_else(|| "No monitor found".to_string())?;
+    let saved = saved_position_on(&monitor);
+
+    window
+        .set_position(tauri::Position::Physical(anchored_top_left(&monitor, saved.as_ref(), window_size.width as i32)))
+        .map_err(|e| format!("Failed to recenter: {}", e))?;
+    Ok(monitor.scale_factor())
+}
+
+/// Get each monitor's work area (MONITORINFO.rcWork on Windows) in logical pixels
+#[cfg(desktop)]
+#[tauri::command]
+fn get_work_area(window: tauri::Window) -> Result<Vec<WorkArea>, String> {
+    let primary_name = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+
+    Ok(monitors
+        .iter()
+        .map(|m| {
+            let name = m.name().cloned().unwrap_or_default();
+            let area = m.work_area();
+            let scale_factor = m.scale_factor();
+            WorkArea {
+                is_primary: primary_name.as_deref() == Some(name.as_str()),
+                monitor: name,
+                x: area.position.x as f64 / scale_factor,
+                y: area.position.y as f64 / scale_factor,
+                width: area.size.width as f64 / scale_factor,
+                height: area.size.height as f64 / scale_factor,
+            }
+        })
+        .collect())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn get_work_area(_window: tauri::Window) -> Result<Vec<WorkArea>, String> {
+    Ok(Vec::new())
+}
+
+/// Get the primary taskbar's edge, auto-hide state and rect (SHAppBarMessage)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_taskbar_info() -> Result<TaskbarInfo, String> {
+    let mut data = APPBARDATA {
+        cbSize: std::mem::size_of::<APPBARDATA>() as u32,
+        ..Default::default()
+    };
+    if unsafe { SHAppBarMessage(ABM_GETTASKBARPOS, &mut data) } == 0 {
+        return Err("Failed to get taskbar position".to_string());
+    }
+    let state = unsafe { SHAppBarMessage(ABM_GETSTATE, &mut data) } as u32;
+
+    let edge = match data.uEdge {
+        ABE_LEFT => "left",
+        ABE_TOP => "top",
+        ABE_RIGHT => "right",
+        _ => "bottom",
+    };
+    let rc = data.rc;
+
+    Ok(TaskbarInfo {
+        edge: edge.to_string(),
+        auto_hide: state & ABS_AUTOHIDE != 0,
+        x: rc.left,
+        y: rc.top,
+        width: rc.right - rc.left,
+        height: rc.bottom - rc.top,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_taskbar_info() -> Result<TaskbarInfo, String> {
+    Err("Taskbar info not supported on this platform".to_string())
+}
+
+#[cfg(desktop)]
+const WINDOW_POSITION_FILE: &str = "window_position.json";
+
+/// The saved position, read from WINDOW_POSITION_FILE once at startup so resizes and
+/// animation frames don't go to disk
+#[cfg(desktop)]
+static SAVED_WINDOW_POSITION: Lazy<std::sync::Mutex<Option<SavedWindowPosition>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Load the saved position into SAVED_WINDOW_POSITION (setup, before the first restore)
+#[cfg(desktop)]
+fn load_saved_window_position(app: &tauri::AppHandle) {
+    *SAVED_WINDOW_POSITION.lock().unwrap_or_else(|e| e.into_inner()) =
+        read_config_file::<SavedWindowPosition>(app, WINDOW_POSITION_FILE);
+}
+
+/// Persist `saved` and make it the position resizes keep
+#[cfg(desktop)]
+fn store_saved_window_position(app: &tauri::AppHandle, saved: SavedWindowPosition) -> Result<(), String> {
+    write_config_file(app, WINDOW_POSITION_FILE, &saved)?;
+    *SAVED_WINDOW_POSITION.lock().unwrap_or_else(|e| e.into_inner()) = Some(saved);
+    Ok(())
+}
+
+/// Remember where the island currently sits. Later launches, position_window and resizes
+/// keep it there until reset_window_position.
+#[cfg(desktop)]
+#[tauri::command]
+fn save_window_position(window: tauri::Window) -> Result<(), String> {
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?;
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| format!("Failed to get monitor: {}", e))?
+        .and_then(|m| m.name().cloned())
+        .ok_or_else(|| "Window is not on a monitor".to_string())?;
+
+    // Physical pixels: logical ones depend on which monitor's DPI they were divided by
+    let saved = SavedWindowPosition {
+        x: position.x + size.width as i32 / 2,
+        y: position.y,
+        monitor,
+    };
+    store_saved_window_position(window.app_handle(), saved)
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn save_window_position(_window: tauri::Window) -> Result<(), String> {
+    Err("Window positioning not supported on mobile".to_string())
+}
+
+/// The saved position and its monitor. None when nothing was saved or the saved monitor is
+/// no longer connected.
+#[cfg(desktop)]
+fn saved_window_placement(
+    monitors: tauri::Result<Vec<tauri::Monitor>>,
+) -> Option<(tauri::Monitor, SavedWindowPosition)> {
+    let saved = SAVED_WINDOW_POSITION.lock().unwrap_or_else(|e| e.into_inner()).clone()?;
+    let monitor = monitors
+        .ok()?
+        .into_iter()
+        .find(|m| m.name() == Some(&saved.monitor))?;
+    Some((monitor, saved))
+}
+
+/// The saved position when it is on `monitor`
+#[cfg(desktop)]
+fn saved_position_on(monitor: &tauri::Monitor) -> Option<SavedWindowPosition> {
+    SAVED_WINDOW_POSITION
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .filter(|saved| monitor.name() == Some(&saved.monitor))
+}
+
+/// Physical top-left that puts a window `width` physical pixels wide at the saved spot
+#[cfg(desktop)]
+fn saved_top_left(saved: &SavedWindowPosition, width: i32) -> tauri::PhysicalPosition<i32> {
+    tauri::PhysicalPosition {
+        x: saved.x - width / 2,
+        y: saved.y,
+    }
+}
+
+/// Move the island back to its saved spot. Returns false (leaving it alone) when nothing
+/// was saved or the saved monitor is no longer connected.
+#[cfg(desktop)]
+fn restore_window_position(window: &tauri::WebviewWindow) -> bool {
+    let Some((_, saved)) = saved_window_placement(window.available_monitors()) else {
+        return false;
+    };
+    let Ok(size) = window.outer_size() else {
+        return false;
+    };
+
+    window
+        .set_position(tauri::Position::Physical(saved_top_left(&saved, size.width as i32)))
+        .is_ok()
+}
+
+/// Forget the saved position and go back to top-center of the primary monitor
+#[cfg(desktop)]
+#[tauri::command]
+fn reset_window_position(window: tauri::Window) -> Result<(), String> {
+    *SAVED_WINDOW_POSITION.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    if let Ok(path) = config_file_path(window.app_handle(), WINDOW_POSITION_FILE) {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove saved position: {}", e))?;
+        }
+    }
+    position_window(window)
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn reset_window_position(_window: tauri::Window) -> Result<(), String> {
+    Err("Window positioning not supported on mobile".to_string())
+}
+
+/// List monitors the window can be placed on
+#[cfg(desktop)]
+#[tauri::command]
+fn list_available_monitors(window: tauri::Window) -> Result<Vec<DisplayMonitor>, String> {
+    let primary_name = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+
+    Ok(monitors
+        .iter()
+        .map(|m| {
+            let name = m.name().cloned().unwrap_or_default();
+            DisplayMonitor {
+                is_primary: primary_name.as_deref() == Some(name.as_str()),
+                name,
+                x: m.position().x,
+                y: m.position().y,
+                width: m.size().width,
+                height: m.size().height,
+                scale_factor: m.scale_factor(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn list_available_monitors(_window: tauri::Window) -> Result<Vec<DisplayMonitor>, String> {
+    Ok(Vec::new())
+}
+
+/// Position window at top-center of the named monitor (a name from list_available_monitors).
+/// A saved position moves along, so later resizes keep the island on that monitor.
+#[cfg(desktop)]
+#[tauri::command]
+fn position_window_on(window: tauri::Window, monitor_name: String) -> Result<(), String> {
+    let monitor = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?
+        .into_iter()
+        .find(|m| m.name().is_some_and(|n| *n == monitor_name))
+        .ok_or_else(|| format!("Monitor {} not found", monitor_name))?;
+
+    let window_size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+
+    let position = top_center_on(&monitor, window_size.width as i32);
+    window
+        .set_position(tauri::Position::Physical(position))
+        .map_err(|e| format!("Failed to position: {}", e))?;
+
+    let has_saved = SAVED_WINDOW_POSITION.lock().unwrap_or_else(|e| e.into_inner()).is_some();
+    if has_saved {
+        store_saved_window_position(window.app_handle(), SavedWindowPosition {
+            x: position.x + window_size.width as i32 / 2,
+            y: position.y,
+            monitor: monitor_name,
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn position_window_on(_window: tauri::Window, _monitor_name: String) -> Result<(), String> {
+    Err("Window positioning not supported on mobile".to_string())
+}
+
+/// Window classes of the desktop and taskbar
+#[cfg(target_os = "windows")]
+const SHELL_WINDOW_CLASSES: [&str; 4] = ["Progman", "WorkerW", "Shell_TrayWnd", "Shell_SecondaryTrayWnd"];
+
+/// Shell processes whose full-screen surfaces (Start, search, Task View) aren't apps
+#[cfg(target_os = "windows")]
+const SHELL_PROCESSES: [&str; 5] = [
+    "explorer.exe",
+    "shellexperiencehost.exe",
+    "startmenuexperiencehost.exe",
+    "searchhost.exe",
+    "searchapp.exe",
+];
+
+/// Whether a window belongs to the desktop or the Windows shell
+#[cfg(target_os = "windows")]
+fn is_shell_window(hwnd: HWND) -> bool {
+    let mut class = [0u16; 256];
+    let len = unsafe { GetClassNameW(hwnd, &mut class) };
+    if len > 0 {
+        let class = String::from_utf16_lossy(&class[..len as usize]);
+        if SHELL_WINDOW_CLASSES.contains(&class.as_str()) {
+            return true;
+        }
+    }
+
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    process_image_path(pid)
+        .and_then(|path| path.rsplit('\\').next().map(str::to_lowercase))
+        .is_some_and(|exe| SHELL_PROCESSES.contains(&exe.as_str()))
+}
+
+/// Check if the foreground window is "content" fullscreen (video/game), not just window fullscreen.
+/// We want: YouTube/Netflix video fullscreen, games → true.
+/// We don't want: browser F11 fullscreen, any app maximized/fullscreen → false.
+/// Uses window style: WS_POPUP or borderless (no caption) = content fullscreen; normal caption = window fullscreen.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn is_foreground_fullscreen(_window: tauri::Window) -> Result<bool, String> {
+    // Never report fullscreen while the user is mid-interaction, or the island hides under the cursor
+    if is_interaction_locked() {
+        return Ok(false);
+    }
 
     // Get foreground window handle
     let hwnd = unsafe { GetForegroundWindow() };
@@ -605,6 +1549,25 @@ fn is_foreground_fullscreen(window: tauri::Window) -> Result<bool, String> {
         return Ok(false);
     }
 
+    // The desktop and shell surfaces are borderless and full-size but never "content"
+    if is_shell_window(hwnd) {
+        return Ok(false);
+    }
+
+    // Compare against the monitor the foreground window is on, not the island's primary
+    // monitor; return false if unavailable (safe default)
+    let hmonitor = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !unsafe { GetMonitorInfoW(hmonitor, &mut info) }.as_bool() {
+        return Ok(false);
+    }
+
+    let mon_w = info.rcMonitor.right - info.rcMonitor.left;
+    let mon_h = info.rcMonitor.bottom - info.rcMonitor.top;
+
     // Get window rectangle
     let mut rect = windows::Win32::Foundation::RECT::default();
     if unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
@@ -652,1389 +1615,7859 @@ fn resize_and_center(window: tauri::Window, width: f64, height: f64) -> Result<(
     if width <= 0.0 || height <= 0.0 {
         return Err("Invalid dimensions".to_string());
     }
+    // An instant resize wins over any animation still in flight
+    RESIZE_ANIMATION_GENERATION.fetch_add(1, Ordering::SeqCst);
     
     // Resize first
     window
         .set_size(tauri::Size::Logical(tauri::LogicalSize { width, height }))
         .map_err(|e| format!("Failed to resize: {}", e))?;
     
-    // Then center
-    if let Ok(Some(monitor)) = window.primary_monitor() {
-        let monitor_size = monitor.size();
-        let scale_factor = monitor.scale_factor();
-        let x = (monitor_size.width as f64 / scale_factor) / 2.0 - width / 2.0;
-        
+    // Then center on whichever monitor the island is on (position_window_on may have moved
+    // it), at the saved spot when that is on the same monitor
+    if let Some((monitor, saved)) = island_anchor(&window) {
+        let physical_width = (width * monitor.scale_factor()).round() as i32;
         window
-            .set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y: 0.0 }))
+            .set_position(tauri::Position::Physical(anchored_top_left(&monitor, saved.as_ref(), physical_width)))
             .map_err(|e| format!("Failed to center: {}", e))?;
     }
     
     Ok(())
 }
 
+/// The monitor the island is on, plus the saved position when it is on that monitor
+#[cfg(desktop)]
+fn island_anchor(window: &tauri::Window) -> Option<(tauri::Monitor, Option<SavedWindowPosition>)> {
+    let monitor = island_monitor(window)?;
+    let saved = saved_position_on(&monitor);
+    Some((monitor, saved))
+}
+
+/// Physical top-left for a window `width` physical pixels wide: the saved spot if any,
+/// otherwise top-center of `monitor`
+#[cfg(desktop)]
+fn anchored_top_left(
+    monitor: &tauri::Monitor,
+    saved: Option<&SavedWindowPosition>,
+    width: i32,
+) -> tauri::PhysicalPosition<i32> {
+    match saved {
+        Some(saved) => saved_top_left(saved, width),
+        None => top_center_on(monitor, width),
+    }
+}
+
+/// The monitor the island is on, falling back to the primary one when it's off-screen
+#[cfg(desktop)]
+fn island_monitor(window: &tauri::Window) -> Option<tauri::Monitor> {
+    window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+/// Physical top-left that top-centers a window `width` physical pixels wide on `monitor`
+#[cfg(desktop)]
+fn top_center_on(monitor: &tauri::Monitor, width: i32) -> tauri::PhysicalPosition<i32> {
+    // Physical coordinates: secondary monitors don't start at x = 0
+    let origin = monitor.position();
+    tauri::PhysicalPosition {
+        x: origin.x + (monitor.size().width as i32 - width) / 2,
+        y: origin.y,
+    }
+}
+
 #[cfg(not(desktop))]
 #[tauri::command]
 fn resize_and_center(_window: tauri::Window, _width: f64, _height: f64) -> Result<(), String> {
     Err("Resize/center not supported on mobile".to_string())
 }
 
-/// Get current monitor scale factor for DPI-aware calculations
-#[tauri::command]
-fn get_scale_factor(window: tauri::Window) -> Result<f64, String> {
-    let monitor = window
-        .primary_monitor()
-        .map_err(|e| format!("Failed to get monitor: {}", e))?
-        .ok_or_else(|| "No primary monitor".to_string())?;
-    
-    Ok(monitor.scale_factor())
+/// Bumped by every resize so a running animation knows it's been superseded
+#[cfg(desktop)]
+static RESIZE_ANIMATION_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(desktop)]
+const RESIZE_ANIMATION_FRAME_MS: u64 = 16; // ~60fps
+
+/// Ease-out cubic: fast start, gentle landing
+#[cfg(desktop)]
+fn ease_out_cubic(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Grow/shrink the window to width x height over duration_ms, keeping it centered on its saved
+/// spot (or top-center of its current monitor) every frame. A newer animate_resize or
+/// resize_and_center cancels it.
+#[cfg(desktop)]
+#[tauri::command]
+fn animate_resize(window: tauri::Window, width: f64, height: f64, duration_ms: u64) -> Result<(), String> {
+    if width <= 0.0 || height <= 0.0 {
+        return Err("Invalid dimensions".to_string());
+    }
+    if duration_ms < RESIZE_ANIMATION_FRAME_MS {
+        return resize_and_center(window, width, height);
+    }
+
+    let (monitor, saved) = island_anchor(&window).ok_or_else(|| "No monitor found".to_string())?;
+    let scale_factor = monitor.scale_factor();
+
+    let start = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?;
+    let (start_w, start_h) = (start.width as f64 / scale_factor, start.height as f64 / scale_factor);
+
+    let generation = RESIZE_ANIMATION_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    thread::spawn(move || {
+        let began = std::time::Instant::now();
+        let duration = Duration::from_millis(duration_ms);
+
+        loop {
+            if RESIZE_ANIMATION_GENERATION.load(Ordering::SeqCst) != generation {
+                return; // Superseded by a newer resize
+            }
+
+            // Pace by wall clock so slow frames shorten the animation instead of stretching it
+            let t = (began.elapsed().as_secs_f64() / duration.as_secs_f64()).min(1.0);
+            let eased = ease_out_cubic(t);
+            let w = start_w + (width - start_w) * eased;
+            let h = start_h + (height - start_h) * eased;
+            let position = anchored_top_left(&monitor, saved.as_ref(), (w * scale_factor).round() as i32);
+
+            let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize { width: w, height: h }));
+            let _ = window.set_position(tauri::Position::Physical(position));
+
+            if t >= 1.0 {
+                return;
+            }
+            thread::sleep(Duration::from_millis(RESIZE_ANIMATION_FRAME_MS));
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+#[tauri::command]
+fn animate_resize(_window: tauri::Window, _width: f64, _height: f64, _duration_ms: u64) -> Result<(), String> {
+    Err("Resize animation not supported on mobile".to_string())
+}
+
+/// Get current monitor scale factor for DPI-aware calculations
+#[tauri::command]
+fn get_scale_factor(window: tauri::Window) -> Result<f64, String> {
+    let monitor = window
+        .primary_monitor()
+        .map_err(|e| format!("Failed to get monitor: {}", e))?
+        .ok_or_else(|| "No primary monitor".to_string())?;
+    
+    Ok(monitor.scale_factor())
 }
 
 // =============================================================================
-// Media Session Commands
+// COM Helpers
 // =============================================================================
 
-/// Helper to get the current media session
+/// RAII COM initialization. Only uninitializes if this guard's CoInitializeEx succeeded, so it's
+/// safe on threads that are already initialized (or already in an STA).
 #[cfg(target_os = "windows")]
-fn get_current_session() -> Result<GlobalSystemMediaTransportControlsSession, String> {
-    let manager = poll_session_manager()?;
-    manager.GetCurrentSession()
-        .map_err(|e| format!("No active media session: {}", e))
+struct ComGuard {
+    initialized: bool,
 }
 
-/// Get current media session info (now playing)
 #[cfg(target_os = "windows")]
+impl ComGuard {
+    fn new() -> Self {
+        let hr = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) };
+        ComGuard { initialized: hr.is_ok() }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+/// Run blocking COM/WinRT work (the POLL_* loops sleep) on Tauri's blocking pool, with COM
+/// initialized on that worker, so async commands never stall the thread that invoked them.
+#[cfg(target_os = "windows")]
+async fn spawn_com_blocking<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(move || {
+        let _com = ComGuard::new();
+        f()
+    })
+    .await
+    .map_err(|e| format!("Background task failed: {}", e))?
+}
+
+/// The MMDevice enumerator is free-threaded, so one instance can serve every command thread.
+#[cfg(target_os = "windows")]
+struct CachedEnumerator(IMMDeviceEnumerator);
+
+#[cfg(target_os = "windows")]
+unsafe impl Send for CachedEnumerator {}
+#[cfg(target_os = "windows")]
+unsafe impl Sync for CachedEnumerator {}
+
+#[cfg(target_os = "windows")]
+static DEVICE_ENUMERATOR: once_cell::sync::OnceCell<CachedEnumerator> = once_cell::sync::OnceCell::new();
+
+/// Helper to get the shared device enumerator, creating it on first use
+#[cfg(target_os = "windows")]
+fn device_enumerator() -> Result<IMMDeviceEnumerator, String> {
+    if let Some(cached) = DEVICE_ENUMERATOR.get() {
+        return Ok(cached.0.clone());
+    }
+
+    let enumerator: IMMDeviceEnumerator = unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
+        .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+    let _ = DEVICE_ENUMERATOR.set(CachedEnumerator(enumerator.clone()));
+    Ok(enumerator)
+}
+
+#[cfg(target_os = "windows")]
+static RUNTIME_PREWARMED: AtomicBool = AtomicBool::new(false);
+
+/// Load COM/WinRT and create the objects the first commands need, on a background thread so
+/// window paint isn't delayed. Without this the first media/audio call after launch is slow.
+#[cfg(target_os = "windows")]
+fn prewarm_runtime() {
+    if RUNTIME_PREWARMED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    thread::spawn(|| {
+        let _com = ComGuard::new();
+        // Keep the MTA alive after this thread exits so the cached enumerator stays usable
+        if let Err(e) = unsafe { CoIncrementMTAUsage() } {
+            eprintln!("[PILLAR] Failed to pin MTA: {}", e);
+        }
+
+        if let Err(e) = device_enumerator() {
+            eprintln!("[PILLAR] Pre-warm: {}", e);
+        }
+        if let Err(e) = poll_session_manager() {
+            eprintln!("[PILLAR] Pre-warm: {}", e);
+        }
+        let _ = UserNotificationListener::Current();
+    });
+}
+
+// =============================================================================
+// Interaction Lock
+// =============================================================================
+
+/// Set while the user is dragging a slider/scrubber so watcher-driven collapse is suppressed.
+static INTERACTION_LOCKED: AtomicBool = AtomicBool::new(false);
+/// Bumped on every lock/unlock so a stale safety timer can't release a newer lock.
+static INTERACTION_LOCK_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Safety net in case the frontend never sends the unlock (e.g. pointerup lost off-window).
+const INTERACTION_LOCK_TIMEOUT_MS: u64 = 10_000;
+
+/// Whether collapse/hide triggers should currently be ignored
+#[cfg(target_os = "windows")]
+fn is_interaction_locked() -> bool {
+    INTERACTION_LOCKED.load(Ordering::Relaxed)
+}
+
+/// Lock or unlock auto-collapse while media/volume controls are being dragged.
+/// A lock auto-releases after INTERACTION_LOCK_TIMEOUT_MS and emits "interaction-lock-changed".
 #[tauri::command]
-fn get_media_session() -> Result<Option<MediaInfo>, String> {
-    // Get session manager
-    let manager = poll_session_manager()?;
+fn set_interaction_lock(app: tauri::AppHandle, locked: bool) -> Result<(), String> {
+    let generation = INTERACTION_LOCK_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    INTERACTION_LOCKED.store(locked, Ordering::SeqCst);
+
+    if locked {
+        thread::spawn(move || {
+            use tauri::Emitter;
+
+            thread::sleep(Duration::from_millis(INTERACTION_LOCK_TIMEOUT_MS));
+            // Only release if nobody re-locked or unlocked in the meantime
+            if INTERACTION_LOCK_GENERATION.load(Ordering::SeqCst) == generation
+                && INTERACTION_LOCKED.swap(false, Ordering::SeqCst)
+            {
+                let _ = app.emit("interaction-lock-changed", false);
+            }
+        });
+    }
 
-    // Get the current session
-    let session = match manager.GetCurrentSession() {
-        Ok(s) => s,
-        Err(_) => {
-            return Ok(None); // No active media session
-        },
-    };
-    
-    // Get playback info
-    let playback_info = session.GetPlaybackInfo()
-        .map_err(|e| format!("Failed to get playback info: {}", e))?;
-    
-    let playback_status = playback_info.PlaybackStatus()
-        .map_err(|e| format!("Failed to get playback status: {}", e))?;
-    
-    let is_playing = playback_status == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing;
-    
-    // Get media properties
-    let properties = poll_media_properties(&session)?;
-    
-    let title = properties.Title()
-        .map(|s: HSTRING| s.to_string())
-        .unwrap_or_default();
-    
-    let artist = properties.Artist()
-        .map(|s: HSTRING| s.to_string())
+    Ok(())
+}
+
+// =============================================================================
+// Foreground App
+// =============================================================================
+
+#[cfg(target_os = "windows")]
+static FOREGROUND_HOOK_APP: once_cell::sync::OnceCell<tauri::AppHandle> = once_cell::sync::OnceCell::new();
+
+/// Title, exe name and PID of a top-level window
+#[cfg(target_os = "windows")]
+fn read_foreground_app(hwnd: HWND) -> Option<ForegroundApp> {
+    if hwnd.0.is_null() {
+        return None;
+    }
+
+    let mut title = [0u16; 512];
+    let len = unsafe { GetWindowTextW(hwnd, &mut title) };
+    let title = String::from_utf16_lossy(&title[..len.max(0) as usize]);
+
+    let mut process_id = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut process_id)) };
+    if process_id == 0 {
+        return None;
+    }
+
+    // Elevated/protected processes can't be opened; still report the title and PID
+    let exe = process_image_path(process_id)
+        .and_then(|path| path.rsplit('\\').next().map(|name| name.to_string()))
         .unwrap_or_default();
-    
-    let album = properties.AlbumTitle()
-        .map(|s: HSTRING| s.to_string())
-        .ok()
-        .filter(|s| !s.is_empty());
-    
-    // Get app name
-    let app_name = session.SourceAppUserModelId()
-        .map(|s: HSTRING| {
-            let s = s.to_string();
-            // Extract app name from the model ID
-            s.split('\\').last()
-                .map(|n| n.trim_end_matches(".exe").to_string())
-                .unwrap_or(s)
-        })
-        .ok();
-    
-    Ok(Some(MediaInfo {
-        title,
-        artist,
-        album,
-        is_playing,
-        app_name,
-    }))
+
+    Some(ForegroundApp { title, exe, process_id })
+}
+
+/// Get the app the user is currently working in
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_foreground_app() -> Result<ForegroundApp, String> {
+    read_foreground_app(unsafe { GetForegroundWindow() })
+        .ok_or_else(|| "No foreground window".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_foreground_app() -> Result<ForegroundApp, String> {
+    Err("Foreground app not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn foreground_event_hook(
+    _hook: HWINEVENTHOOK,
+    event: u32,
+    hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    // App switches can collapse the island; not while the user is dragging a control
+    if event != EVENT_SYSTEM_FOREGROUND || is_interaction_locked() {
+        return;
+    }
+    if let (Some(app), Some(info)) = (FOREGROUND_HOOK_APP.get(), read_foreground_app(hwnd)) {
+        use tauri::Emitter;
+        let _ = app.emit("foreground-changed", info);
+    }
+}
+
+/// Emit "foreground-changed" (ForegroundApp) on app switches. Out-of-context WinEvent hooks
+/// are delivered through the installing thread's message loop, so call this from setup on
+/// the main thread. Our own window is skipped. The hook lives as long as the app.
+#[cfg(target_os = "windows")]
+fn install_foreground_hook(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let _ = FOREGROUND_HOOK_APP.set(app_handle.clone());
+
+    let hook = unsafe {
+        SetWinEventHook(
+            EVENT_SYSTEM_FOREGROUND,
+            EVENT_SYSTEM_FOREGROUND,
+            None,
+            Some(foreground_event_hook),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        )
+    };
+    if hook.is_invalid() {
+        return Err("Failed to install foreground hook".to_string());
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Foreground Window Color
+// =============================================================================
+
+/// Format a Win32 COLORREF (0x00BBGGRR) as "#RRGGBB"
+#[cfg(target_os = "windows")]
+fn colorref_to_hex(color: u32) -> String {
+    let r = color & 0xFF;
+    let g = (color >> 8) & 0xFF;
+    let b = (color >> 16) & 0xFF;
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+/// Get the foreground window's title-bar color as "#RRGGBB" so the island can match the app
+/// it's floating over. Uses the caption color the app set via DWM (Windows 11), otherwise
+/// samples a pixel from the title bar. None for borderless windows without a title bar.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_foreground_window_color() -> Result<Option<String>, String> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    if hwnd.0.is_null() {
+        return Ok(None);
+    }
+
+    // 1. Explicit caption color. Fails on Windows 10, where the attribute doesn't exist.
+    let mut caption: u32 = 0;
+    let has_caption_color = unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CAPTION_COLOR,
+            &mut caption as *mut u32 as *mut _,
+            std::mem::size_of::<u32>() as u32,
+        )
+    }
+    .is_ok();
+    if has_caption_color && caption != DWMWA_COLOR_DEFAULT && caption != DWMWA_COLOR_NONE {
+        return Ok(Some(colorref_to_hex(caption)));
+    }
+
+    // Borderless/popup windows (games, video players) have no title bar worth sampling
+    let style = unsafe { GetWindowLongPtrW(hwnd, GWL_STYLE) } as u32;
+    if (style & WS_CAPTION.0) != WS_CAPTION.0 {
+        return Ok(None);
+    }
+
+    // 2. Sample the title bar. Use the visible frame bounds; GetWindowRect includes the
+    // invisible resize border on Windows 10+.
+    let mut rect = windows::Win32::Foundation::RECT::default();
+    let has_frame_bounds = unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut _ as *mut _,
+            std::mem::size_of::<windows::Win32::Foundation::RECT>() as u32,
+        )
+    }
+    .is_ok();
+    if !has_frame_bounds && unsafe { GetWindowRect(hwnd, &mut rect) }.is_err() {
+        return Ok(None);
+    }
+    if rect.right - rect.left < 300 {
+        return Ok(None);
+    }
+
+    // Between the title text and the caption buttons, a few pixels below the top edge
+    let x = rect.right - 200;
+    let y = rect.top + 8;
+
+    let color = unsafe {
+        let hdc = GetDC(None);
+        if hdc.is_invalid() {
+            return Err("Failed to get screen DC".to_string());
+        }
+        let color = GetPixel(hdc, x, y);
+        ReleaseDC(None, hdc);
+        color
+    };
+
+    // CLR_INVALID when the point is off-screen (e.g. window partly outside the desktop)
+    if color.0 == CLR_INVALID {
+        return Ok(None);
+    }
+    Ok(Some(colorref_to_hex(color.0)))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_foreground_window_color() -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+// =============================================================================
+// Top Edge Hover
+// =============================================================================
+
+/// Bumped to stop the current watcher thread (restart or app exit)
+#[cfg(desktop)]
+static TOP_EDGE_WATCH_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Hot zone (width, height) in logical pixels, centered on the primary monitor's top edge
+static TOP_EDGE_HOT_ZONE: Lazy<std::sync::Mutex<(f64, f64)>> =
+    Lazy::new(|| std::sync::Mutex::new((300.0, 4.0)));
+
+#[cfg(target_os = "windows")]
+const TOP_EDGE_POLL_MS: u64 = 50;
+
+/// Whether the cursor is inside the hot zone. Tauri processes are per-monitor DPI aware, so
+/// SM_CXSCREEN and the cursor are physical; the zone is scaled by the system DPI.
+#[cfg(target_os = "windows")]
+fn cursor_in_top_edge_zone() -> Option<bool> {
+    let (zone_w, zone_h) = *TOP_EDGE_HOT_ZONE.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut cursor = windows::Win32::Foundation::POINT::default();
+    unsafe { GetCursorPos(&mut cursor) }.ok()?;
+
+    let scale = unsafe {
+        let hdc = GetDC(None);
+        if hdc.is_invalid() {
+            return None;
+        }
+        let dpi = GetDeviceCaps(hdc, LOGPIXELSX);
+        ReleaseDC(None, hdc);
+        dpi as f64 / 96.0
+    };
+    let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) } as f64;
+
+    let half_w = zone_w * scale / 2.0;
+    let x = cursor.x as f64;
+    let y = cursor.y as f64;
+    Some(y >= 0.0 && y < zone_h * scale && x >= screen_w / 2.0 - half_w && x < screen_w / 2.0 + half_w)
+}
+
+/// Emit "top-edge-hover" when the cursor enters the hot zone and "top-edge-leave" when it
+/// exits, so a hidden island can reveal itself. Only transitions are emitted.
+#[cfg(target_os = "windows")]
+fn watch_top_edge_hover(app_handle: tauri::AppHandle) {
+    let generation = TOP_EDGE_WATCH_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    thread::spawn(move || {
+        use tauri::Emitter;
+
+        let mut inside = false;
+        while TOP_EDGE_WATCH_GENERATION.load(Ordering::SeqCst) == generation {
+            if let Some(now_inside) = cursor_in_top_edge_zone() {
+                if now_inside != inside {
+                    inside = now_inside;
+                    let event = if inside { "top-edge-hover" } else { "top-edge-leave" };
+                    let _ = app_handle.emit(event, ());
+                }
+            }
+            thread::sleep(Duration::from_millis(TOP_EDGE_POLL_MS));
+        }
+    });
+}
+
+/// Stop the hover watcher (called on quit)
+#[cfg(desktop)]
+fn stop_top_edge_hover() {
+    TOP_EDGE_WATCH_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Set the top-edge hot zone size in logical pixels
+#[tauri::command]
+fn set_top_edge_hot_zone(width: f64, height: f64) -> Result<(), String> {
+    if width <= 0.0 || height <= 0.0 {
+        return Err("Invalid hot zone dimensions".to_string());
+    }
+    *TOP_EDGE_HOT_ZONE.lock().unwrap_or_else(|e| e.into_inner()) = (width, height);
+    Ok(())
+}
+
+/// Start or stop the top-edge hover watcher
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_top_edge_hover_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        watch_top_edge_hover(app);
+    } else {
+        stop_top_edge_hover();
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_top_edge_hover_enabled(_app: tauri::AppHandle, _enabled: bool) -> Result<(), String> {
+    Err("Top edge hover not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Window Message Hook
+// =============================================================================
+
+/// App handle for the subclass proc, which has no other way to reach Tauri
+#[cfg(target_os = "windows")]
+static MESSAGE_HOOK_APP: once_cell::sync::OnceCell<tauri::AppHandle> = once_cell::sync::OnceCell::new();
+
+#[cfg(target_os = "windows")]
+const MESSAGE_HOOK_ID: usize = 1;
+
+/// Subclass proc on the main window. Top-level windows receive system broadcasts
+/// (WM_SETTINGCHANGE, ...), so we turn the ones the UI cares about into events.
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn window_message_hook(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _ref_data: usize,
+) -> LRESULT {
+    // Monitors may have come or gone; cached DDC/CI handles could be stale
+    if msg == WM_DISPLAYCHANGE {
+        invalidate_physical_monitors();
+    }
+
+    if let Some(app) = MESSAGE_HOOK_APP.get() {
+        if msg == WM_SETTINGCHANGE && lparam.0 != 0 {
+            // lParam names the changed setting
+            let setting = windows::core::PCWSTR(lparam.0 as *const u16)
+                .to_string()
+                .unwrap_or_default();
+            // "ConvertibleSlateMode" = 2-in-1 flipped; "UserInteractionMode" = Windows 10 tablet mode
+            if setting == "ConvertibleSlateMode" || setting == "UserInteractionMode" {
+                use tauri::Emitter;
+                let _ = app.emit("posture-changed", read_tablet_mode());
+            }
+        }
+
+        // Sent on resolution changes as well as Win+P projection switches
+        if msg == WM_DISPLAYCHANGE {
+            if let Ok(topology) = read_display_topology() {
+                let mut last = LAST_DISPLAY_TOPOLOGY.lock().unwrap_or_else(|e| e.into_inner());
+                if last.as_deref() != Some(topology.as_str()) {
+                    *last = Some(topology.clone());
+                    use tauri::Emitter;
+                    let _ = app.emit("topology-changed", topology);
+                }
+            }
+        }
+    }
+
+    let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+
+    // Dock/undock, resolution or scale changes: recenter once tao has applied the new DPI size
+    if msg == WM_DISPLAYCHANGE || msg == WM_DPICHANGED {
+        if let Some(app) = MESSAGE_HOOK_APP.get() {
+            match recenter_main_window(app) {
+                Ok(scale_factor) => {
+                    use tauri::Emitter;
+                    let _ = app.emit("display-changed", scale_factor);
+                }
+                Err(e) => eprintln!("[PILLAR] {}", e),
+            }
+        }
+    }
+    result
+}
+
+/// Install window_message_hook on the main window. Must run on the thread that owns the window.
+#[cfg(target_os = "windows")]
+fn install_window_message_hook(window: &tauri::WebviewWindow) -> Result<(), String> {
+    let _ = MESSAGE_HOOK_APP.set(window.app_handle().clone());
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {}", e))?;
+    let installed = unsafe {
+        SetWindowSubclass(HWND(hwnd.0), Some(window_message_hook), MESSAGE_HOOK_ID, 0)
+    };
+    if !installed.as_bool() {
+        return Err("Failed to subclass main window".to_string());
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Media Session Commands
+// =============================================================================
+
+/// Helper to get the current media session, or None when nothing is playing
+#[cfg(target_os = "windows")]
+fn current_media_session() -> Result<Option<GlobalSystemMediaTransportControlsSession>, String> {
+    let manager = poll_session_manager()?;
+    match manager.GetCurrentSession() {
+        Ok(session) => Ok(Some(session)),
+        Err(e) => {
+            invalidate_session_manager(&e);
+            Ok(None)
+        }
+    }
+}
+
+/// Helper to get every media session
+#[cfg(target_os = "windows")]
+fn all_media_sessions() -> Result<Vec<GlobalSystemMediaTransportControlsSession>, String> {
+    let manager = poll_session_manager()?;
+    match manager.GetSessions() {
+        Ok(sessions) => Ok(sessions.into_iter().collect()),
+        Err(e) => {
+            invalidate_session_manager(&e);
+            Err(format!("Failed to get media sessions: {}", e))
+        }
+    }
+}
+
+/// Helper to get the current media session
+#[cfg(target_os = "windows")]
+fn get_current_session() -> Result<GlobalSystemMediaTransportControlsSession, String> {
+    current_media_session()?.ok_or_else(|| "No active media session".to_string())
+}
+
+/// Helper to find the session for an app by its AUMID (MediaInfo.session_id)
+#[cfg(target_os = "windows")]
+fn find_session(app_id: &str) -> Result<GlobalSystemMediaTransportControlsSession, String> {
+    all_media_sessions()?
+        .into_iter()
+        .find(|session| {
+            session.SourceAppUserModelId()
+                .map(|id| id.to_string().eq_ignore_ascii_case(app_id))
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| format!("No session for {}", app_id))
+}
+
+/// Windows FILETIME epoch (1601-01-01) to Unix epoch, in 100ns ticks
+#[cfg(target_os = "windows")]
+const FILETIME_UNIX_EPOCH_TICKS: i64 = 116_444_736_000_000_000;
+
+/// Convert 100ns ticks to milliseconds, clamping negatives to 0
+#[cfg(target_os = "windows")]
+fn ticks_to_ms(ticks: i64) -> u64 {
+    (ticks.max(0) / 10_000) as u64
+}
+
+/// Helper to read (position_ms, duration_ms, last_updated_ms) from a session's timeline.
+/// Returns zeros when the app doesn't publish a timeline.
+#[cfg(target_os = "windows")]
+fn read_timeline(session: &GlobalSystemMediaTransportControlsSession) -> (u64, u64, u64) {
+    let Ok(timeline) = session.GetTimelineProperties() else {
+        return (0, 0, 0);
+    };
+
+    let start = timeline.StartTime().map(|t| t.Duration).unwrap_or(0);
+    let end = timeline.EndTime().map(|t| t.Duration).unwrap_or(0);
+    let position = timeline.Position().map(|t| t.Duration).unwrap_or(0);
+
+    // EndTime is zero for live streams; report unknown duration rather than a negative one
+    let duration_ms = if end > start { ticks_to_ms(end - start) } else { 0 };
+    let position_ms = ticks_to_ms(position - start);
+
+    let last_updated_ms = timeline
+        .LastUpdatedTime()
+        .map(|t| ticks_to_ms(t.UniversalTime - FILETIME_UNIX_EPOCH_TICKS))
+        .unwrap_or(0);
+
+    (position_ms, duration_ms, last_updated_ms)
+}
+
+/// Helper to read which transport controls a session supports.
+/// If the app doesn't report them, only play/pause is assumed to work.
+#[cfg(target_os = "windows")]
+fn read_media_controls(
+    playback_info: &windows::Media::Control::GlobalSystemMediaTransportControlsSessionPlaybackInfo,
+) -> MediaControls {
+    let Ok(c) = playback_info.Controls() else {
+        return MediaControls {
+            play_pause: true,
+            play: false,
+            pause: false,
+            stop: false,
+            next: false,
+            previous: false,
+            fast_forward: false,
+            rewind: false,
+            shuffle: false,
+            repeat: false,
+            seek: false,
+        };
+    };
+
+    MediaControls {
+        play_pause: c.IsPlayPauseToggleEnabled().unwrap_or(true),
+        play: c.IsPlayEnabled().unwrap_or(false),
+        pause: c.IsPauseEnabled().unwrap_or(false),
+        stop: c.IsStopEnabled().unwrap_or(false),
+        next: c.IsNextEnabled().unwrap_or(false),
+        previous: c.IsPreviousEnabled().unwrap_or(false),
+        fast_forward: c.IsFastForwardEnabled().unwrap_or(false),
+        rewind: c.IsRewindEnabled().unwrap_or(false),
+        shuffle: c.IsShuffleEnabled().unwrap_or(false),
+        repeat: c.IsRepeatEnabled().unwrap_or(false),
+        seek: c.IsPlaybackPositionEnabled().unwrap_or(false),
+    }
+}
+
+/// Helper to build MediaInfo for one session
+#[cfg(target_os = "windows")]
+fn build_media_info(session: &GlobalSystemMediaTransportControlsSession) -> Result<MediaInfo, String> {
+    // Get playback info
+    let playback_info = session.GetPlaybackInfo()
+        .map_err(|e| format!("Failed to get playback info: {}", e))?;
+    
+    let playback_status = playback_info.PlaybackStatus()
+        .map_err(|e| format!("Failed to get playback status: {}", e))?;
+    
+    let is_playing = playback_status == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing;
+
+    // Null references (player doesn't report them) come back as errors
+    let shuffle = playback_info.IsShuffleActive()
+        .and_then(|r| r.Value())
+        .ok();
+    let repeat_mode = playback_info.AutoRepeatMode()
+        .and_then(|r| r.Value())
+        .ok()
+        .map(|mode| match mode {
+            MediaPlaybackAutoRepeatMode::Track => "track",
+            MediaPlaybackAutoRepeatMode::List => "list",
+            _ => "none",
+        }.to_string());
+    let controls = read_media_controls(&playback_info);
+    let playback_rate = playback_info.PlaybackRate()
+        .and_then(|r| r.Value())
+        .ok()
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+        .unwrap_or(1.0);
+    
+    // Get media properties
+    let properties = poll_media_properties(session)?;
+    
+    let title = properties.Title()
+        .map(|s: HSTRING| s.to_string())
+        .unwrap_or_default();
+    
+    let artist = properties.Artist()
+        .map(|s: HSTRING| s.to_string())
+        .unwrap_or_default();
+    
+    let album = properties.AlbumTitle()
+        .map(|s: HSTRING| s.to_string())
+        .ok()
+        .filter(|s| !s.is_empty());
+    
+    let genres = properties.Genres()
+        .map(|list| {
+            list.into_iter()
+                .map(|g| g.to_string())
+                .filter(|g| !g.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let content_type = match properties.PlaybackType().and_then(|t| t.Value()) {
+        Ok(MediaPlaybackType::Music) => "music",
+        Ok(MediaPlaybackType::Video) => "video",
+        Ok(MediaPlaybackType::Image) => "image",
+        _ => "unknown",
+    }.to_string();
+
+    // Players that don't set these report 0
+    let track_number = properties.TrackNumber().ok().filter(|&n| n > 0);
+    let album_track_count = properties.AlbumTrackCount().ok().filter(|&n| n > 0);
+
+    let session_id = session.SourceAppUserModelId()
+        .map(|s: HSTRING| s.to_string())
+        .unwrap_or_default();
+
+    // Get app name
+    let app_name = Some(&session_id)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            // Extract app name from the model ID
+            s.split('\\').last()
+                .map(|n| n.trim_end_matches(".exe").to_string())
+                .unwrap_or_else(|| s.clone())
+        });
+
+    let (position_ms, duration_ms, last_updated_ms) = read_timeline(session);
+    
+    Ok(MediaInfo {
+        session_id,
+        title,
+        artist,
+        album,
+        is_playing,
+        app_name,
+        position_ms,
+        duration_ms,
+        last_updated_ms,
+        shuffle,
+        repeat_mode,
+        playback_rate,
+        controls,
+        genres,
+        track_number,
+        album_track_count,
+        content_type,
+    })
+}
+
+/// Get current media session info (now playing)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_media_session() -> Result<Option<MediaInfo>, String> {
+    // Get the current session
+    let session = match current_media_session()? {
+        Some(s) => s,
+        None => {
+            return Ok(None); // No active media session
+        },
+    };
+
+    build_media_info(&session).map(Some)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_media_session() -> Result<Option<MediaInfo>, String> {
+    Ok(None)
+}
+
+/// List every media session (e.g. Spotify and a browser video at once)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_media_sessions() -> Result<Vec<MediaInfo>, String> {
+    let sessions = all_media_sessions()?;
+
+    let mut result = Vec::new();
+    for session in sessions {
+        // One misbehaving app shouldn't hide the others
+        match build_media_info(&session) {
+            Ok(info) => result.push(info),
+            Err(e) => eprintln!("[PILLAR] Skipping media session: {}", e),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn list_media_sessions() -> Result<Vec<MediaInfo>, String> {
+    Ok(Vec::new())
+}
+
+/// Play/pause media
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_play_pause() -> Result<(), String> {
+    let session = get_current_session()?;
+    
+    let op = session.TryTogglePlayPauseAsync()
+        .map_err(|e| format!("Failed to toggle play/pause: {}", e))?;
+    
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_play_pause() -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Skip to next track
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_next() -> Result<(), String> {
+    let session = get_current_session()?;
+    
+    let op = session.TrySkipNextAsync()
+        .map_err(|e| format!("Failed to skip next: {}", e))?;
+    
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_next() -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Skip to previous track
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_previous() -> Result<(), String> {
+    let session = get_current_session()?;
+    
+    let op = session.TrySkipPreviousAsync()
+        .map_err(|e| format!("Failed to skip previous: {}", e))?;
+    
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_previous() -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Stop playback (some apps release audio focus on stop but not on pause)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_stop() -> Result<(), String> {
+    let session = get_current_session()?;
+
+    let op = session.TryStopAsync()
+        .map_err(|e| format!("Failed to stop: {}", e))?;
+
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_stop() -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Seek to a position in ms from the start of the track (as reported in MediaInfo.position_ms)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_seek(position_ms: u64) -> Result<(), String> {
+    let session = get_current_session()?;
+
+    // SMTC positions are absolute 100ns ticks; read_timeline reports them relative to StartTime
+    let start = session.GetTimelineProperties()
+        .and_then(|timeline| timeline.StartTime())
+        .map(|t| t.Duration)
+        .unwrap_or(0);
+    let ticks = (position_ms as i64).saturating_mul(10_000).saturating_add(start);
+    let op = session.TryChangePlaybackPositionAsync(ticks)
+        .map_err(|e| format!("Failed to seek: {}", e))?;
+
+    if !poll_bool_op(op)? {
+        return Err("Current media session does not support seeking".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_seek(_position_ms: u64) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Skip forward/back by a relative offset (e.g. +10000 / -10000 ms).
+/// Position-based rather than TryFastForward/TryRewind, whose step size varies per app.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_skip_relative(offset_ms: i64) -> Result<(), String> {
+    let session = get_current_session()?;
+    let (position_ms, duration_ms, last_updated_ms) = read_timeline(&session);
+
+    if duration_ms == 0 && offset_ms > 0 {
+        return Err("Cannot skip forward: track duration is unknown".to_string());
+    }
+
+    // The reported position is a snapshot; extrapolate it while playing
+    let playback_info = session.GetPlaybackInfo()
+        .map_err(|e| format!("Failed to get playback info: {}", e))?;
+    let is_playing = playback_info.PlaybackStatus()
+        .map(|s| s == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing)
+        .unwrap_or(false);
+    let mut current_ms = position_ms as i64;
+    if is_playing && last_updated_ms > 0 {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let rate = playback_info.PlaybackRate()
+            .and_then(|r| r.Value())
+            .unwrap_or(1.0);
+        current_ms += ((now_ms - last_updated_ms as i64).max(0) as f64 * rate) as i64;
+    }
+
+    let mut target_ms = current_ms.saturating_add(offset_ms).max(0);
+    if duration_ms > 0 {
+        target_ms = target_ms.min(duration_ms as i64);
+    }
+
+    media_seek(target_ms as u64)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_skip_relative(_offset_ms: i64) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Turn shuffle on or off for the current session
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_set_shuffle(enabled: bool) -> Result<(), String> {
+    let session = get_current_session()?;
+
+    let op = session.TryChangeShuffleActiveAsync(enabled)
+        .map_err(|e| format!("Failed to change shuffle: {}", e))?;
+
+    if !poll_bool_op(op)? {
+        return Err("Current media session does not support shuffle".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_set_shuffle(_enabled: bool) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Set repeat mode for the current session: "none" | "track" | "list"
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_set_repeat(mode: String) -> Result<(), String> {
+    let repeat = match mode.as_str() {
+        "none" => MediaPlaybackAutoRepeatMode::None,
+        "track" => MediaPlaybackAutoRepeatMode::Track,
+        "list" => MediaPlaybackAutoRepeatMode::List,
+        _ => return Err(format!("Unknown repeat mode: {}", mode)),
+    };
+
+    let session = get_current_session()?;
+
+    let op = session.TryChangeAutoRepeatModeAsync(repeat)
+        .map_err(|e| format!("Failed to change repeat mode: {}", e))?;
+
+    if !poll_bool_op(op)? {
+        return Err("Current media session does not support repeat".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_set_repeat(_mode: String) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Play/pause a specific app's session
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_play_pause_for(app_id: String) -> Result<(), String> {
+    let session = find_session(&app_id)?;
+
+    let op = session.TryTogglePlayPauseAsync()
+        .map_err(|e| format!("Failed to toggle play/pause: {}", e))?;
+
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_play_pause_for(_app_id: String) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Skip to next track in a specific app's session
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_next_for(app_id: String) -> Result<(), String> {
+    let session = find_session(&app_id)?;
+
+    let op = session.TrySkipNextAsync()
+        .map_err(|e| format!("Failed to skip next: {}", e))?;
+
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_next_for(_app_id: String) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+/// Skip to previous track in a specific app's session
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn media_previous_for(app_id: String) -> Result<(), String> {
+    let session = find_session(&app_id)?;
+
+    let op = session.TrySkipPreviousAsync()
+        .map_err(|e| format!("Failed to skip previous: {}", e))?;
+
+    let _success = poll_bool_op(op)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn media_previous_for(_app_id: String) -> Result<(), String> {
+    Err("Media controls not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Media Session Events
+// =============================================================================
+
+/// Live event registrations. The manager and session must stay alive or their events stop firing.
+#[cfg(target_os = "windows")]
+struct MediaSubscriptions {
+    manager: GlobalSystemMediaTransportControlsSessionManager,
+    _manager_token: EventRegistrationToken,
+    // (session, MediaPropertiesChanged token, PlaybackInfoChanged token)
+    session: Option<(
+        GlobalSystemMediaTransportControlsSession,
+        EventRegistrationToken,
+        EventRegistrationToken,
+    )>,
+}
+
+#[cfg(target_os = "windows")]
+static MEDIA_SUBSCRIPTIONS: Lazy<std::sync::Mutex<Option<MediaSubscriptions>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Emit "media-changed" with the current session's MediaInfo (null when nothing is playing)
+#[cfg(target_os = "windows")]
+fn emit_media_changed(app_handle: &tauri::AppHandle) {
+    use tauri::Emitter;
+    match get_media_session() {
+        Ok(info) => {
+            let _ = app_handle.emit("media-changed", info);
+        }
+        Err(e) => eprintln!("[PILLAR] Failed to read media session for event: {}", e),
+    }
+}
+
+/// Move the property/playback handlers onto the manager's current session
+#[cfg(target_os = "windows")]
+fn attach_current_media_session(app_handle: &tauri::AppHandle) {
+    let mut guard = MEDIA_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(subs) = guard.as_mut() else {
+        return;
+    };
+
+    if let Some((old, properties_token, playback_token)) = subs.session.take() {
+        let _ = old.RemoveMediaPropertiesChanged(properties_token);
+        let _ = old.RemovePlaybackInfoChanged(playback_token);
+    }
+
+    let Ok(session) = subs.manager.GetCurrentSession() else {
+        return; // Nothing playing; CurrentSessionChanged will fire when something starts
+    };
+
+    let handle_for_properties = app_handle.clone();
+    let properties_handler = TypedEventHandler::new(
+        move |_session: &Option<GlobalSystemMediaTransportControlsSession>,
+              _args: &Option<MediaPropertiesChangedEventArgs>| {
+            emit_media_changed(&handle_for_properties);
+            Ok(())
+        },
+    );
+    let handle_for_playback = app_handle.clone();
+    let playback_handler = TypedEventHandler::new(
+        move |_session: &Option<GlobalSystemMediaTransportControlsSession>,
+              _args: &Option<PlaybackInfoChangedEventArgs>| {
+            emit_media_changed(&handle_for_playback);
+            Ok(())
+        },
+    );
+
+    match (
+        session.MediaPropertiesChanged(&properties_handler),
+        session.PlaybackInfoChanged(&playback_handler),
+    ) {
+        (Ok(properties_token), Ok(playback_token)) => {
+            subs.session = Some((session, properties_token, playback_token));
+        }
+        (properties, playback) => {
+            eprintln!("[PILLAR] Failed to subscribe to media session events; polling still works");
+            if let Ok(token) = properties {
+                let _ = session.RemoveMediaPropertiesChanged(token);
+            }
+            if let Ok(token) = playback {
+                let _ = session.RemovePlaybackInfoChanged(token);
+            }
+        }
+    }
+}
+
+/// Subscribe to SMTC changes so the frontend gets "media-changed" instead of polling.
+/// get_media_session stays available as the polling fallback.
+#[cfg(target_os = "windows")]
+fn subscribe_media_changed(app_handle: &tauri::AppHandle) -> bool {
+    let manager = match poll_session_manager() {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("[PILLAR] Media events unavailable: {}", e);
+            return false;
+        }
+    };
+
+    let handle_for_event = app_handle.clone();
+    let handler = TypedEventHandler::new(
+        move |_manager: &Option<GlobalSystemMediaTransportControlsSessionManager>,
+              _args: &Option<CurrentSessionChangedEventArgs>| {
+            attach_current_media_session(&handle_for_event);
+            emit_media_changed(&handle_for_event);
+            Ok(())
+        },
+    );
+
+    let token = match manager.CurrentSessionChanged(&handler) {
+        Ok(token) => token,
+        Err(e) => {
+            eprintln!("[PILLAR] Failed to subscribe to CurrentSessionChanged: {:?}", e);
+            return false;
+        }
+    };
+
+    *MEDIA_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner()) = Some(MediaSubscriptions {
+        manager,
+        _manager_token: token,
+        session: None,
+    });
+    attach_current_media_session(app_handle);
+    eprintln!("[PILLAR] Successfully subscribed to media session events");
+    true
+}
+
+// =============================================================================
+// Media Theme (artwork-derived accent color)
+// =============================================================================
+
+/// Matches the frontend's default media accent (Tailwind blue-400)
+const DEFAULT_MEDIA_ACCENT: &str = "#60A5FA";
+/// Artwork is decoded at this size; plenty for a dominant color and cheap to scan
+#[cfg(target_os = "windows")]
+const THEME_SAMPLE_SIZE: u32 = 48;
+
+/// Last computed theme, keyed by track, so repeated calls don't re-decode the artwork
+#[cfg(target_os = "windows")]
+static MEDIA_THEME_CACHE: Lazy<std::sync::Mutex<Option<(String, MediaTheme)>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Helper to open the session thumbnail stream. Returns None when the player has no artwork.
+#[cfg(target_os = "windows")]
+fn open_media_thumbnail(
+    properties: &GlobalSystemMediaTransportControlsSessionMediaProperties,
+) -> Result<Option<IRandomAccessStreamWithContentType>, String> {
+    let reference = match properties.Thumbnail() {
+        Ok(r) => r,
+        Err(_) => return Ok(None),
+    };
+
+    let op = reference.OpenReadAsync()
+        .map_err(|e| format!("Failed to open thumbnail: {}", e))?;
+
+    poll_async_op(op, "thumbnail stream").map(Some)
+}
+
+/// Helper to read a whole stream (from its current position) into memory
+#[cfg(target_os = "windows")]
+fn read_stream_bytes(stream: &IRandomAccessStream) -> Result<Vec<u8>, String> {
+    let size = stream.Size()
+        .map_err(|e| format!("Failed to get stream size: {}", e))? as u32;
+    let reader = DataReader::CreateDataReader(stream)
+        .map_err(|e| format!("Failed to create data reader: {}", e))?;
+
+    let op: windows::Foundation::IAsyncOperation<u32> = reader.LoadAsync(size)
+        .map_err(|e| format!("Failed to load stream: {}", e))?
+        .cast()
+        .map_err(|e| format!("Failed to load stream: {}", e))?;
+    let loaded = poll_async_op(op, "stream bytes")?;
+
+    let mut bytes = vec![0u8; loaded as usize];
+    reader.ReadBytes(&mut bytes)
+        .map_err(|e| format!("Failed to read stream: {}", e))?;
+    Ok(bytes)
+}
+
+/// Sniff an image MIME type from its magic bytes
+#[cfg(target_os = "windows")]
+fn sniff_image_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0x89, b'P', b'N', b'G']) {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"BM") {
+        Some("image/bmp")
+    } else {
+        None
+    }
+}
+
+/// Decode the thumbnail into a small RGBA8 buffer for color analysis
+#[cfg(target_os = "windows")]
+fn decode_thumbnail_pixels(stream: &IRandomAccessStreamWithContentType) -> Result<Vec<u8>, String> {
+    let decoder = poll_async_op(
+        BitmapDecoder::CreateAsync(stream).map_err(|e| format!("Failed to create decoder: {}", e))?,
+        "image decoder",
+    )?;
+
+    let transform = BitmapTransform::new()
+        .map_err(|e| format!("Failed to create transform: {}", e))?;
+    let _ = transform.SetScaledWidth(THEME_SAMPLE_SIZE);
+    let _ = transform.SetScaledHeight(THEME_SAMPLE_SIZE);
+
+    let op = decoder
+        .GetPixelDataTransformedAsync(
+            BitmapPixelFormat::Rgba8,
+            BitmapAlphaMode::Straight,
+            &transform,
+            ExifOrientationMode::IgnoreExifOrientation,
+            ColorManagementMode::DoNotColorManage,
+        )
+        .map_err(|e| format!("Failed to decode thumbnail: {}", e))?;
+
+    let provider = poll_async_op(op, "pixel data")?;
+    let pixels = provider.DetachPixelData()
+        .map_err(|e| format!("Failed to read pixel data: {}", e))?;
+
+    Ok(pixels.to_vec())
+}
+
+/// Compute (accent "#RRGGBB", is_dark) from RGBA8 pixels.
+/// The accent is the most common saturated color bucket; grey/near-black pixels are
+/// ignored so album borders and backgrounds don't win. Falls back to the plain average.
+#[cfg(target_os = "windows")]
+fn analyze_artwork(pixels: &[u8]) -> (String, bool) {
+    // 3 bits per channel -> 512 buckets of (count, r_sum, g_sum, b_sum)
+    let mut buckets = vec![(0u32, 0u32, 0u32, 0u32); 512];
+    let (mut total, mut lum_sum) = (0u32, 0f64);
+    let (mut r_all, mut g_all, mut b_all) = (0u32, 0u32, 0u32);
+
+    for px in pixels.chunks_exact(4) {
+        if px[3] < 128 {
+            continue; // transparent
+        }
+        let (r, g, b) = (px[0] as u32, px[1] as u32, px[2] as u32);
+        total += 1;
+        r_all += r;
+        g_all += g;
+        b_all += b;
+        lum_sum += (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64) / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        if max < 40 || max - min < 24 {
+            continue;
+        }
+
+        let idx = ((r >> 5) << 6 | (g >> 5) << 3 | (b >> 5)) as usize;
+        let bucket = &mut buckets[idx];
+        bucket.0 += 1;
+        bucket.1 += r;
+        bucket.2 += g;
+        bucket.3 += b;
+    }
+
+    if total == 0 {
+        return (DEFAULT_MEDIA_ACCENT.to_string(), false);
+    }
+
+    let is_dark = lum_sum / (total as f64) < 0.5;
+
+    let (count, r, g, b) = buckets
+        .into_iter()
+        .max_by_key(|bucket| bucket.0)
+        .filter(|bucket| bucket.0 > 0)
+        .unwrap_or((total, r_all, g_all, b_all));
+
+    let accent = format!("#{:02X}{:02X}{:02X}", r / count, g / count, b / count);
+    (accent, is_dark)
+}
+
+/// Get a theme for the now-playing card: artwork accent color, luminance and media type.
+/// Returns defaults when nothing is playing or the session has no artwork.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_media_theme() -> Result<MediaTheme, String> {
+    let session = match current_media_session()? {
+        Some(s) => s,
+        None => {
+            return Ok(MediaTheme {
+                accent_color: DEFAULT_MEDIA_ACCENT.to_string(),
+                is_video: false,
+                is_dark_art: false,
+            });
+        }
+    };
+
+    let properties = poll_media_properties(&session)?;
+
+    let is_video = properties
+        .PlaybackType()
+        .and_then(|t| t.Value())
+        .map(|t| t == MediaPlaybackType::Video)
+        .unwrap_or(false);
+
+    // Cache by app + track so the artwork is only decoded once per song
+    let track_key = format!(
+        "{}|{}|{}|{}",
+        session.SourceAppUserModelId().map(|s| s.to_string()).unwrap_or_default(),
+        properties.Title().map(|s| s.to_string()).unwrap_or_default(),
+        properties.Artist().map(|s| s.to_string()).unwrap_or_default(),
+        properties.AlbumTitle().map(|s| s.to_string()).unwrap_or_default(),
+    );
+
+    if let Ok(cache) = MEDIA_THEME_CACHE.lock() {
+        if let Some((key, theme)) = cache.as_ref() {
+            if *key == track_key {
+                return Ok(theme.clone());
+            }
+        }
+    }
+
+    // None until the artwork is readable; SMTC often publishes the title before the art
+    let analyzed = match open_media_thumbnail(&properties) {
+        Ok(Some(stream)) => decode_thumbnail_pixels(&stream)
+            .ok()
+            .filter(|pixels| !pixels.is_empty())
+            .map(|pixels| analyze_artwork(&pixels)),
+        _ => None,
+    };
+
+    let Some((accent_color, is_dark_art)) = analyzed else {
+        // Not cached, so the next call retries once the artwork arrives
+        return Ok(MediaTheme {
+            accent_color: DEFAULT_MEDIA_ACCENT.to_string(),
+            is_video,
+            is_dark_art: false,
+        });
+    };
+
+    let theme = MediaTheme {
+        accent_color,
+        is_video,
+        is_dark_art,
+    };
+
+    if let Ok(mut cache) = MEDIA_THEME_CACHE.lock() {
+        *cache = Some((track_key, theme.clone()));
+    }
+
+    Ok(theme)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_media_theme() -> Result<MediaTheme, String> {
+    Ok(MediaTheme {
+        accent_color: DEFAULT_MEDIA_ACCENT.to_string(),
+        is_video: false,
+        is_dark_art: false,
+    })
+}
+
+/// Get the current session's album art as a data URL ("data:image/png;base64,...").
+/// Ok(None) when the session has no artwork, which is common.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_media_thumbnail() -> Result<Option<String>, String> {
+    let session = match current_media_session()? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let properties = poll_media_properties(&session)?;
+
+    let stream = match open_media_thumbnail(&properties)? {
+        Some(stream) => stream,
+        None => return Ok(None),
+    };
+    let bytes = read_stream_bytes(&stream.cast().map_err(|e| format!("Failed to read thumbnail: {}", e))?)?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+
+    // Trust the header over the stream's ContentType, which some apps leave blank
+    let mime = sniff_image_mime(&bytes)
+        .map(|m| m.to_string())
+        .or_else(|| stream.ContentType().ok().map(|t| t.to_string()).filter(|t| t.starts_with("image/")))
+        .unwrap_or_else(|| "image/jpeg".to_string());
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    Ok(Some(format!("data:{};base64,{}", mime, encoded)))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_media_thumbnail() -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+// =============================================================================
+// Volume Control Commands
+// =============================================================================
+
+/// Map an optional role name to an ERole: "console" (default) | "communications" | "multimedia"
+#[cfg(target_os = "windows")]
+fn parse_audio_role(role: Option<&str>) -> Result<ERole, String> {
+    match role.unwrap_or("console") {
+        "console" => Ok(eConsole),
+        "communications" => Ok(eCommunications),
+        "multimedia" => Ok(eMultimedia),
+        other => Err(format!("Unknown audio role: {}", other)),
+    }
+}
+
+/// Helper to get the default render device for a role
+#[cfg(target_os = "windows")]
+fn default_render_device(role: ERole) -> Result<IMMDevice, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let enumerator = device_enumerator()?;
+
+        enumerator.GetDefaultAudioEndpoint(eRender, role)
+            .map_err(|e| format!("Failed to get audio endpoint: {}", e))
+    }
+}
+
+/// Helper to get the endpoint volume interface of the default render device for a role
+#[cfg(target_os = "windows")]
+fn render_endpoint_volume(role: ERole) -> Result<IAudioEndpointVolume, String> {
+    let device = default_render_device(role)?;
+    unsafe {
+        device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))
+    }
+}
+
+/// Helper to read level, mute state and identity of an endpoint
+#[cfg(target_os = "windows")]
+fn read_volume_info(device: &IMMDevice) -> Result<VolumeInfo, String> {
+    unsafe {
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+
+        // Get volume level (0.0 - 1.0)
+        let level = volume.GetMasterVolumeLevelScalar()
+            .map_err(|e| format!("Failed to get volume level: {}", e))?;
+
+        // Get mute state
+        let is_muted = volume.GetMute()
+            .map_err(|e| format!("Failed to get mute state: {}", e))?
+            .as_bool();
+
+        Ok(VolumeInfo {
+            level: (level * 100.0).round() as u32,
+            is_muted,
+            device_name: get_device_name(device).unwrap_or_default(),
+            device_id: get_device_id(device).unwrap_or_default(),
+        })
+    }
+}
+
+/// Helper to get the endpoint volume interface of the default (console) render device
+#[cfg(target_os = "windows")]
+fn default_endpoint_volume() -> Result<IAudioEndpointVolume, String> {
+    render_endpoint_volume(eConsole)
+}
+
+/// Get system volume. `role` picks the default device: "console" (default) or
+/// "communications" (the call device, which can differ during meetings).
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_system_volume(role: Option<String>) -> Result<VolumeInfo, String> {
+    let device = default_render_device(parse_audio_role(role.as_deref())?)?;
+    read_volume_info(&device)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_system_volume(_role: Option<String>) -> Result<VolumeInfo, String> {
+    Ok(VolumeInfo {
+        level: 0,
+        is_muted: false,
+        device_name: String::new(),
+        device_id: String::new(),
+    })
+}
+
+/// Set system volume (0-100). `role` as in get_system_volume.
+/// Like the Windows volume flyout, a non-zero level unmutes unless `unmute_on_change` is false.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_system_volume(level: u32, role: Option<String>, unmute_on_change: Option<bool>) -> Result<(), String> {
+    if level > 100 {
+        return Err("Volume level must be 0-100".to_string());
+    }
+    
+    let volume = render_endpoint_volume(parse_audio_role(role.as_deref())?)?;
+
+    unsafe {
+        volume.SetMasterVolumeLevelScalar(level as f32 / 100.0, std::ptr::null())
+            .map_err(|e| format!("Failed to set volume: {}", e))?;
+
+        // Setting 0 keeps the mute state as-is
+        if level > 0 && unmute_on_change.unwrap_or(true) {
+            let is_muted = volume.GetMute().map(|m| m.as_bool()).unwrap_or(false);
+            if is_muted {
+                volume.SetMute(false, std::ptr::null())
+                    .map_err(|e| format!("Failed to unmute: {}", e))?;
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_system_volume(
+    _level: u32,
+    _role: Option<String>,
+    _unmute_on_change: Option<bool>,
+) -> Result<(), String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+/// Toggle mute
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn toggle_mute() -> Result<bool, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        
+        let enumerator = device_enumerator()?;
+        
+        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
+            .map_err(|e| format!("Failed to get audio endpoint: {}", e))?;
+        
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+        
+        let is_muted = volume.GetMute()
+            .map_err(|e| format!("Failed to get mute state: {}", e))?
+            .as_bool();
+        
+        volume.SetMute(!is_muted, std::ptr::null())
+            .map_err(|e| format!("Failed to toggle mute: {}", e))?;
+        
+        Ok(!is_muted)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn toggle_mute() -> Result<bool, String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+/// Change system volume by a relative amount (e.g. +2 / -2) and return the new state.
+/// Read-modify-write happens here so rapid volume key presses never act on stale frontend state.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn adjust_system_volume(delta: i32) -> Result<VolumeInfo, String> {
+    let device = default_render_device(eConsole)?;
+
+    unsafe {
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+
+        let current = volume.GetMasterVolumeLevelScalar()
+            .map_err(|e| format!("Failed to get volume level: {}", e))?;
+
+        let current = (current * 100.0).round() as i32;
+        let level = current.saturating_add(delta).clamp(0, 100) as u32;
+
+        volume.SetMasterVolumeLevelScalar(level as f32 / 100.0, std::ptr::null())
+            .map_err(|e| format!("Failed to set volume: {}", e))?;
+    }
+
+    read_volume_info(&device)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn adjust_system_volume(_delta: i32) -> Result<VolumeInfo, String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+/// Windows volume keys move the master volume in 2-point steps
+const VOLUME_KEY_STEP: i32 = 2;
+
+/// Nudge system volume one hardware-key step up or down, so island buttons stay in sync with
+/// the physical keys
+#[tauri::command]
+fn volume_step(up: bool) -> Result<VolumeInfo, String> {
+    adjust_system_volume(if up { VOLUME_KEY_STEP } else { -VOLUME_KEY_STEP })
+}
+
+/// Helper to get the default device's endpoint volume, checking it's stereo
+#[cfg(target_os = "windows")]
+fn stereo_endpoint_volume() -> Result<IAudioEndpointVolume, String> {
+    let volume = default_endpoint_volume()?;
+    let channels = unsafe { volume.GetChannelCount() }
+        .map_err(|e| format!("Failed to get channel count: {}", e))?;
+    if channels != 2 {
+        return Err(format!(
+            "Balance needs a stereo device (current device has {} channel{})",
+            channels,
+            if channels == 1 { "" } else { "s" }
+        ));
+    }
+    Ok(volume)
+}
+
+/// Get left/right channel levels of the default output device
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_volume_balance() -> Result<VolumeBalance, String> {
+    let volume = stereo_endpoint_volume()?;
+    unsafe {
+        let left = volume.GetChannelVolumeLevelScalar(0)
+            .map_err(|e| format!("Failed to get left channel: {}", e))?;
+        let right = volume.GetChannelVolumeLevelScalar(1)
+            .map_err(|e| format!("Failed to get right channel: {}", e))?;
+        Ok(VolumeBalance { left, right })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_volume_balance() -> Result<VolumeBalance, String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+/// Set left/right channel levels (each 0.0-1.0) of the default output device
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_volume_balance(left: f32, right: f32) -> Result<(), String> {
+    let volume = stereo_endpoint_volume()?;
+    unsafe {
+        volume.SetChannelVolumeLevelScalar(0, left.clamp(0.0, 1.0), std::ptr::null())
+            .map_err(|e| format!("Failed to set left channel: {}", e))?;
+        volume.SetChannelVolumeLevelScalar(1, right.clamp(0.0, 1.0), std::ptr::null())
+            .map_err(|e| format!("Failed to set right channel: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_volume_balance(_left: f32, _right: f32) -> Result<(), String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+/// Get the current output peak (0.0-1.0) of the default render device for a VU-style visualizer.
+/// This is the actual signal amplitude after mixing, not the volume setting: it reads 0 while
+/// nothing plays, even at 100% volume. Cheap enough to poll at ~30fps.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_master_peak() -> Result<f32, String> {
+    let device = default_render_device(eConsole)?;
+    unsafe {
+        let meter: IAudioMeterInformation = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get meter interface: {}", e))?;
+
+        meter.GetPeakValue()
+            .map_err(|e| format!("Failed to get peak value: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_master_peak() -> Result<f32, String> {
+    Ok(0.0)
+}
+
+/// Bumped by every fade so an in-flight fade thread knows it has been superseded
+#[cfg(target_os = "windows")]
+static VOLUME_FADE_GENERATION: AtomicU64 = AtomicU64::new(0);
+#[cfg(target_os = "windows")]
+const VOLUME_FADE_STEP_MS: u64 = 16;
+
+/// Fade system volume to `target` (0-100) over `duration_ms` instead of jumping.
+/// Returns immediately; a newer fade cancels this one.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn fade_system_volume(target: u32, duration_ms: u64) -> Result<(), String> {
+    let target = target.min(100) as f32 / 100.0;
+    let generation = VOLUME_FADE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    thread::spawn(move || {
+        let _com = ComGuard::new();
+        let volume = match default_endpoint_volume() {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("[PILLAR] Volume fade failed: {}", e);
+                return;
+            }
+        };
+
+        unsafe {
+            let start = volume.GetMasterVolumeLevelScalar().unwrap_or(target);
+            let steps = (duration_ms / VOLUME_FADE_STEP_MS).max(1);
+
+            for step in 1..steps {
+                if VOLUME_FADE_GENERATION.load(Ordering::SeqCst) != generation {
+                    return; // Superseded by a newer fade
+                }
+                let level = start + (target - start) * (step as f32 / steps as f32);
+                let _ = volume.SetMasterVolumeLevelScalar(level, std::ptr::null());
+                thread::sleep(Duration::from_millis(VOLUME_FADE_STEP_MS));
+            }
+
+            // Land exactly on target, not on an interpolated float
+            if VOLUME_FADE_GENERATION.load(Ordering::SeqCst) == generation {
+                let _ = volume.SetMasterVolumeLevelScalar(target, std::ptr::null());
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn fade_system_volume(_target: u32, _duration_ms: u64) -> Result<(), String> {
+    Err("Volume control not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Microphone Commands
+// =============================================================================
+
+/// Helper to get the default capture device used for calls
+#[cfg(target_os = "windows")]
+fn default_capture_device() -> Result<IMMDevice, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let enumerator = device_enumerator()?;
+
+        enumerator.GetDefaultAudioEndpoint(eCapture, eCommunications)
+            .map_err(|e| format!("Failed to get microphone endpoint: {}", e))
+    }
+}
+
+/// Get microphone volume and mute state
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_mic_volume() -> Result<VolumeInfo, String> {
+    read_volume_info(&default_capture_device()?)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_mic_volume() -> Result<VolumeInfo, String> {
+    Err("Microphone control not supported on this platform".to_string())
+}
+
+/// Set microphone volume (0-100)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_mic_volume(level: u32) -> Result<VolumeInfo, String> {
+    if level > 100 {
+        return Err("Volume level must be 0-100".to_string());
+    }
+
+    let device = default_capture_device()?;
+    unsafe {
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+
+        volume.SetMasterVolumeLevelScalar(level as f32 / 100.0, std::ptr::null())
+            .map_err(|e| format!("Failed to set microphone volume: {}", e))?;
+    }
+
+    read_volume_info(&device)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_mic_volume(_level: u32) -> Result<VolumeInfo, String> {
+    Err("Microphone control not supported on this platform".to_string())
+}
+
+/// Toggle microphone mute (quick-mute during calls)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn toggle_mic_mute() -> Result<VolumeInfo, String> {
+    let device = default_capture_device()?;
+    unsafe {
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+
+        let is_muted = volume.GetMute()
+            .map_err(|e| format!("Failed to get mute state: {}", e))?
+            .as_bool();
+
+        volume.SetMute(!is_muted, std::ptr::null())
+            .map_err(|e| format!("Failed to toggle microphone mute: {}", e))?;
+    }
+
+    read_volume_info(&device)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn toggle_mic_mute() -> Result<VolumeInfo, String> {
+    Err("Microphone control not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Audio Device Commands
+// =============================================================================
+
+/// Helper to get device friendly name from IMMDevice using Windows Property Store
+#[cfg(target_os = "windows")]
+fn get_device_name(device: &IMMDevice) -> Result<String, String> {
+    unsafe {
+        // Open the property store for read access
+        let store: IPropertyStore = device.OpenPropertyStore(STGM_READ)
+            .map_err(|e| format!("Failed to open property store: {}", e))?;
+        
+        // Get the friendly name property
+        let value = store.GetValue(&PKEY_Device_FriendlyName)
+            .map_err(|e| format!("Failed to get device name property: {}", e))?;
+        
+        // Extract string from PROPVARIANT using Windows API (allocates; we must free)
+        if let Ok(pwstr) = PropVariantToStringAlloc(&value) {
+            if !pwstr.0.is_null() {
+                let len = (0..).take_while(|&i| *pwstr.0.add(i) != 0).count();
+                let slice = std::slice::from_raw_parts(pwstr.0, len);
+                let name = String::from_utf16_lossy(slice);
+                CoTaskMemFree(Some(pwstr.0 as *const _));
+                if !name.is_empty() {
+                    return Ok(name);
+                }
+            }
+        }
+        
+        // Fallback: try to get a name from the device ID
+        let id = get_device_id(device)?;
+        let short_id = if id.len() > 8 { &id[id.len()-8..] } else { &id };
+        Ok(format!("Audio Device {}", short_id))
+    }
+}
+
+/// Helper to get device ID from IMMDevice
+#[cfg(target_os = "windows")]
+fn get_device_id(device: &IMMDevice) -> Result<String, String> {
+    unsafe {
+        let id = device.GetId()
+            .map_err(|e| format!("Failed to get device ID: {}", e))?;
+        
+        // Convert PWSTR to String
+        let len = (0..).take_while(|&i| *id.0.add(i) != 0).count();
+        let slice = std::slice::from_raw_parts(id.0, len);
+        let id_str = String::from_utf16_lossy(slice);
+        
+        // Free the string
+        windows::Win32::System::Com::CoTaskMemFree(Some(id.0 as *const _));
+        
+        Ok(id_str)
+    }
+}
+
+/// Helper to read (form_factor, icon_path) so the UI can pick a headphones/speakers/HDMI icon
+#[cfg(target_os = "windows")]
+fn get_device_appearance(device: &IMMDevice) -> (String, Option<String>) {
+    unsafe {
+        let store: IPropertyStore = match device.OpenPropertyStore(STGM_READ) {
+            Ok(s) => s,
+            Err(_) => return ("Unknown".to_string(), None),
+        };
+
+        // EndpointFormFactor enum values
+        let form_factor = store.GetValue(&PKEY_AudioEndpoint_FormFactor)
+            .and_then(|value| PropVariantToUInt32(&value))
+            .map(|value| match value {
+                0 => "RemoteNetworkDevice",
+                1 => "Speakers",
+                2 => "LineLevel",
+                3 => "Headphones",
+                4 => "Microphone",
+                5 => "Headset",
+                6 => "Handset",
+                7 => "UnknownDigitalPassthrough",
+                8 => "SPDIF",
+                9 => "DigitalAudioDisplayDevice",
+                _ => "Unknown",
+            })
+            .unwrap_or("Unknown")
+            .to_string();
+
+        let icon_path = store.GetValue(&PKEY_DeviceClass_IconPath)
+            .and_then(|value| PropVariantToStringAlloc(&value))
+            .map(take_co_task_string)
+            .ok()
+            .filter(|path| !path.is_empty());
+
+        (form_factor, icon_path)
+    }
+}
+
+/// Helper to map an endpoint state to the string used in AudioDevice
+#[cfg(target_os = "windows")]
+fn device_state_name(state: DEVICE_STATE) -> &'static str {
+    match state {
+        DEVICE_STATE_ACTIVE => "active",
+        DEVICE_STATE_DISABLED => "disabled",
+        DEVICE_STATE_UNPLUGGED => "unplugged",
+        _ => "notpresent",
+    }
+}
+
+/// Helper to list endpoints of one data flow, flagging the default for `role`.
+/// Only active endpoints unless `include_inactive` (disabled/unplugged are added for a full picker).
+#[cfg(target_os = "windows")]
+fn list_endpoints(flow: EDataFlow, role: ERole, include_inactive: bool) -> Result<Vec<AudioDevice>, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        
+        let enumerator = device_enumerator()?;
+        
+        // Get default device ID for comparison (there may be none, e.g. no microphone)
+        let default_id = enumerator.GetDefaultAudioEndpoint(flow, role)
+            .ok()
+            .and_then(|device| get_device_id(&device).ok())
+            .unwrap_or_default();
+        
+        let state_mask = if include_inactive {
+            DEVICE_STATE(DEVICE_STATE_ACTIVE.0 | DEVICE_STATE_DISABLED.0 | DEVICE_STATE_UNPLUGGED.0)
+        } else {
+            DEVICE_STATE_ACTIVE
+        };
+        
+        let collection: IMMDeviceCollection = enumerator.EnumAudioEndpoints(flow, state_mask)
+            .map_err(|e| format!("Failed to enumerate devices: {}", e))?;
+        
+        let count = collection.GetCount()
+            .map_err(|e| format!("Failed to get device count: {}", e))?;
+        
+        let mut devices = Vec::new();
+        
+        for i in 0..count {
+            let device = collection.Item(i)
+                .map_err(|e| format!("Failed to get device {}: {}", i, e))?;
+            
+            let id = get_device_id(&device)?;
+            let name = get_device_name(&device).unwrap_or_else(|_| format!("Audio Device {}", i + 1));
+            let is_default = id == default_id;
+            
+            let (form_factor, icon_path) = get_device_appearance(&device);
+            let state = device.GetState()
+                .map(device_state_name)
+                .unwrap_or("notpresent")
+                .to_string();
+            
+            devices.push(AudioDevice {
+                id,
+                name,
+                is_default,
+                form_factor,
+                icon_path,
+                state,
+            });
+        }
+        
+        Ok(devices)
+    }
+}
+
+/// List audio output devices. Active only unless `include_inactive` is true.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_audio_devices(include_inactive: Option<bool>) -> Result<Vec<AudioDevice>, String> {
+    list_endpoints(eRender, eMultimedia, include_inactive.unwrap_or(false))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn list_audio_devices(_include_inactive: Option<bool>) -> Result<Vec<AudioDevice>, String> {
+    Ok(Vec::new())
+}
+
+/// List all audio input (microphone) devices; is_default marks the communications default
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_input_devices() -> Result<Vec<AudioDevice>, String> {
+    list_endpoints(eCapture, eCommunications, false)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn list_input_devices() -> Result<Vec<AudioDevice>, String> {
+    Ok(Vec::new())
+}
+
+/// Get the default audio device
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_default_audio_device() -> Result<AudioDevice, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        
+        let enumerator = device_enumerator()?;
+        
+        let device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)
+            .map_err(|e| format!("Failed to get default device: {}", e))?;
+        
+        let id = get_device_id(&device)?;
+        let name = get_device_name(&device)?;
+        let (form_factor, icon_path) = get_device_appearance(&device);
+        
+        Ok(AudioDevice {
+            id,
+            name,
+            is_default: true,
+            form_factor,
+            icon_path,
+            state: "active".to_string(),
+        })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_default_audio_device() -> Result<AudioDevice, String> {
+    Err("Audio devices not supported on this platform".to_string())
+}
+
+/// Helper to find an active endpoint (render or capture) by its device ID
+#[cfg(target_os = "windows")]
+fn find_endpoint(device_id: &str) -> Result<IMMDevice, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        
+        let enumerator = device_enumerator()?;
+        
+        let collection: IMMDeviceCollection = enumerator.EnumAudioEndpoints(eAll, DEVICE_STATE_ACTIVE)
+            .map_err(|e| format!("Failed to enumerate devices: {}", e))?;
+        
+        let count = collection.GetCount()
+            .map_err(|e| format!("Failed to get device count: {}", e))?;
+        
+        for i in 0..count {
+            let device = collection.Item(i)
+                .map_err(|e| format!("Failed to get device {}: {}", i, e))?;
+            
+            if get_device_id(&device).map(|id| id == device_id).unwrap_or(false) {
+                return Ok(device);
+            }
+        }
+        
+        Err(format!("Audio device not found: {}", device_id))
+    }
+}
+
+/// Get the volume of a specific device, e.g. to preview a non-default output in the device list
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_device_volume(device_id: String) -> Result<VolumeInfo, String> {
+    let device = find_endpoint(&device_id)?;
+    read_volume_info(&device)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_device_volume(_device_id: String) -> Result<VolumeInfo, String> {
+    Err("Audio devices not supported on this platform".to_string())
+}
+
+/// Set the volume (0-100) of a specific device without making it the default
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_device_volume(device_id: String, level: u32) -> Result<(), String> {
+    if level > 100 {
+        return Err("Volume level must be 0-100".to_string());
+    }
+    
+    let device = find_endpoint(&device_id)?;
+    
+    unsafe {
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+        
+        volume.SetMasterVolumeLevelScalar(level as f32 / 100.0, std::ptr::null())
+            .map_err(|e| format!("Failed to set volume: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_device_volume(_device_id: String, _level: u32) -> Result<(), String> {
+    Err("Audio devices not supported on this platform".to_string())
+}
+
+/// Get the channel count of a device (1 = mono, 2 = stereo, 6 = 5.1, ...) so the UI can
+/// hide the balance slider where it makes no sense
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_device_channels(device_id: String) -> Result<u32, String> {
+    let device = find_endpoint(&device_id)?;
+    
+    unsafe {
+        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+        
+        volume.GetChannelCount()
+            .map_err(|e| format!("Failed to get channel count: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_device_channels(_device_id: String) -> Result<u32, String> {
+    Err("Audio devices not supported on this platform".to_string())
+}
+
+/// Undocumented IPolicyConfig (Windows 7+), the interface the Sound control panel uses to change
+/// the default endpoint. There is no public API for this; only SetDefaultEndpoint is called,
+/// the rest are declared to keep the vtable layout right.
+#[cfg(target_os = "windows")]
+#[allow(non_snake_case)]
+mod policy_config {
+    use std::ffi::c_void;
+    use windows::core::{HRESULT, PCWSTR};
+    use windows::Win32::Media::Audio::ERole;
+
+    #[windows::core::interface("f8679f50-850a-41cf-9c72-430f290290c8")]
+    pub unsafe trait IPolicyConfig: windows::core::IUnknown {
+        unsafe fn GetMixFormat(&self, device_id: PCWSTR, format: *mut *mut c_void) -> HRESULT;
+        unsafe fn GetDeviceFormat(&self, device_id: PCWSTR, default: i32, format: *mut *mut c_void) -> HRESULT;
+        unsafe fn ResetDeviceFormat(&self, device_id: PCWSTR) -> HRESULT;
+        unsafe fn SetDeviceFormat(&self, device_id: PCWSTR, endpoint_format: *mut c_void, mix_format: *mut c_void) -> HRESULT;
+        unsafe fn GetProcessingPeriod(&self, device_id: PCWSTR, default: i32, default_period: *mut i64, min_period: *mut i64) -> HRESULT;
+        unsafe fn SetProcessingPeriod(&self, device_id: PCWSTR, period: *mut i64) -> HRESULT;
+        unsafe fn GetShareMode(&self, device_id: PCWSTR, mode: *mut c_void) -> HRESULT;
+        unsafe fn SetShareMode(&self, device_id: PCWSTR, mode: *mut c_void) -> HRESULT;
+        unsafe fn GetPropertyValue(&self, device_id: PCWSTR, key: *const c_void, value: *mut c_void) -> HRESULT;
+        unsafe fn SetPropertyValue(&self, device_id: PCWSTR, key: *const c_void, value: *mut c_void) -> HRESULT;
+        unsafe fn SetDefaultEndpoint(&self, device_id: PCWSTR, role: ERole) -> HRESULT;
+        unsafe fn SetEndpointVisibility(&self, device_id: PCWSTR, visible: i32) -> HRESULT;
+    }
+
+    // The generated wrappers are private to this module
+    impl IPolicyConfig {
+        pub unsafe fn set_default_endpoint(&self, device_id: PCWSTR, role: ERole) -> windows::core::Result<()> {
+            self.SetDefaultEndpoint(device_id, role).ok()
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+use policy_config::IPolicyConfig;
+
+/// CLSID of CPolicyConfigClient, which implements IPolicyConfig
+#[cfg(target_os = "windows")]
+const POLICY_CONFIG_CLIENT: windows::core::GUID = windows::core::GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+
+/// Helper to make an endpoint the default for every role, like "Set as default" in Sound settings
+#[cfg(target_os = "windows")]
+fn set_default_endpoint(device_id: &str) -> Result<(), String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        
+        let policy: IPolicyConfig = CoCreateInstance(&POLICY_CONFIG_CLIENT, None, CLSCTX_ALL)
+            .map_err(|e| format!("Failed to create policy config: {}", e))?;
+        
+        let wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+        let id = windows::core::PCWSTR(wide.as_ptr());
+        
+        for role in [eConsole, eMultimedia, eCommunications] {
+            policy.set_default_endpoint(id, role)
+                .map_err(|e| format!("Failed to set default device: {}", e))?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// Make `device_id` the default output/input device. The endpoint notification client emits
+/// "audio-devices-changed" for the switch.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_default_audio_device(device_id: String) -> Result<(), String> {
+    // Validates the ID against active endpoints before touching the policy
+    find_endpoint(&device_id)?;
+    set_default_endpoint(&device_id)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_default_audio_device(_device_id: String) -> Result<(), String> {
+    Err("Audio devices not supported on this platform".to_string())
+}
+
+/// Switch the default output to the next active device (wrapping) and return it
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn cycle_default_output() -> Result<AudioDevice, String> {
+    let devices = list_endpoints(eRender, eMultimedia, false)?;
+    if devices.is_empty() {
+        return Err("No audio output devices".to_string());
+    }
+    
+    // With no default (or none active) start from the first device
+    let next_index = devices.iter()
+        .position(|d| d.is_default)
+        .map(|i| (i + 1) % devices.len())
+        .unwrap_or(0);
+    
+    let mut next = devices[next_index].clone();
+    set_default_endpoint(&next.id)?;
+    
+    next.is_default = true;
+    Ok(next)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn cycle_default_output() -> Result<AudioDevice, String> {
+    Err("Audio devices not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Audio Device Events
+// =============================================================================
+
+/// Forwards endpoint changes (headphones unplugged, default switched, ...) to the frontend as
+/// "audio-devices-changed". Payload is the kind of change; the frontend just re-queries.
+#[cfg(target_os = "windows")]
+#[windows::core::implement(IMMNotificationClient)]
+struct AudioDeviceNotifier {
+    app_handle: tauri::AppHandle,
+}
+
+#[cfg(target_os = "windows")]
+impl AudioDeviceNotifier {
+    fn emit(&self, change: &str) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit("audio-devices-changed", change);
+    }
+}
+
+// Callbacks run on a system thread and must not block
+#[cfg(target_os = "windows")]
+impl IMMNotificationClient_Impl for AudioDeviceNotifier_Impl {
+    fn OnDeviceStateChanged(&self, _device_id: &windows::core::PCWSTR, _new_state: DEVICE_STATE) -> windows::core::Result<()> {
+        self.emit("state");
+        Ok(())
+    }
+
+    fn OnDeviceAdded(&self, _device_id: &windows::core::PCWSTR) -> windows::core::Result<()> {
+        self.emit("added");
+        Ok(())
+    }
+
+    fn OnDeviceRemoved(&self, _device_id: &windows::core::PCWSTR) -> windows::core::Result<()> {
+        self.emit("removed");
+        Ok(())
+    }
+
+    // Fires once per role (console/multimedia/communications), so expect bursts
+    fn OnDefaultDeviceChanged(&self, flow: EDataFlow, role: ERole, _device_id: &windows::core::PCWSTR) -> windows::core::Result<()> {
+        self.emit("default");
+
+        // Session events follow the default output; re-subscribing activates devices, which
+        // must not happen on this callback thread
+        if flow == eRender && role == eConsole {
+            let app_handle = self.app_handle.clone();
+            thread::spawn(move || {
+                let _com = ComGuard::new();
+                resubscribe_audio_sessions(&app_handle);
+            });
+        }
+        Ok(())
+    }
+
+    // Volume/format property churn; not interesting to the device list
+    fn OnPropertyValueChanged(
+        &self,
+        _device_id: &windows::core::PCWSTR,
+        _key: &windows::Win32::UI::Shell::PropertiesSystem::PROPERTYKEY,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+/// Registered client, kept for the app lifetime (unregistered clients stop receiving callbacks)
+#[cfg(target_os = "windows")]
+struct RegisteredNotifier {
+    _client: IMMNotificationClient,
+}
+
+#[cfg(target_os = "windows")]
+unsafe impl Send for RegisteredNotifier {}
+#[cfg(target_os = "windows")]
+unsafe impl Sync for RegisteredNotifier {}
+
+#[cfg(target_os = "windows")]
+static AUDIO_DEVICE_NOTIFIER: once_cell::sync::OnceCell<RegisteredNotifier> = once_cell::sync::OnceCell::new();
+
+/// Register for endpoint notifications on the shared device enumerator
+#[cfg(target_os = "windows")]
+fn subscribe_audio_devices_changed(app_handle: &tauri::AppHandle) -> bool {
+    if AUDIO_DEVICE_NOTIFIER.get().is_some() {
+        return true;
+    }
+
+    let enumerator = match device_enumerator() {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("[PILLAR] Audio device events unavailable: {}", e);
+            return false;
+        }
+    };
+
+    let client: IMMNotificationClient = AudioDeviceNotifier {
+        app_handle: app_handle.clone(),
+    }
+    .into();
+
+    match unsafe { enumerator.RegisterEndpointNotificationCallback(&client) } {
+        Ok(()) => {
+            let _ = AUDIO_DEVICE_NOTIFIER.set(RegisteredNotifier { _client: client });
+            eprintln!("[PILLAR] Successfully subscribed to audio device changes");
+            true
+        }
+        Err(e) => {
+            eprintln!("[PILLAR] Failed to subscribe to audio device changes: {:?}", e);
+            false
+        }
+    }
+}
+
+// =============================================================================
+// Per-App Volume Commands
+// =============================================================================
+
+/// Helper to get the full exe path of a process. Fails for elevated/protected processes.
+#[cfg(target_os = "windows")]
+fn process_image_path(process_id: u32) -> Option<String> {
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            windows::core::PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(process);
+        
+        if result.is_err() {
+            return None;
+        }
+        Some(String::from_utf16_lossy(&buffer[..size as usize]))
+    }
+}
+
+/// Helper to read FileDescription from an exe's version resource ("Spotify", "Google Chrome")
+#[cfg(target_os = "windows")]
+fn file_description(path: &str) -> Option<String> {
+    unsafe {
+        let path = windows::core::HSTRING::from(path);
+        
+        let size = GetFileVersionInfoSizeW(&path, None);
+        if size == 0 {
+            return None;
+        }
+        
+        let mut data = vec![0u8; size as usize];
+        if GetFileVersionInfoW(&path, 0, size, data.as_mut_ptr() as *mut _).is_err() {
+            return None;
+        }
+        
+        // Query a string value from the version block; returns None if missing
+        let query = |sub_block: &str| -> Option<(*mut std::ffi::c_void, u32)> {
+            let mut value: *mut std::ffi::c_void = std::ptr::null_mut();
+            let mut len = 0u32;
+            let sub_block = windows::core::HSTRING::from(sub_block);
+            if VerQueryValueW(data.as_ptr() as *const _, &sub_block, &mut value, &mut len).as_bool()
+                && !value.is_null()
+                && len > 0
+            {
+                Some((value, len))
+            } else {
+                None
+            }
+        };
+        
+        // First language/codepage pair, falling back to US English/Unicode
+        let translation = query("\\VarFileInfo\\Translation")
+            .filter(|&(_, len)| len >= 4)
+            .map(|(value, _)| {
+                let pair = value as *const u16;
+                (*pair, *pair.add(1))
+            })
+            .unwrap_or((0x0409, 0x04b0));
+        
+        let (value, len) = query(&format!(
+            "\\StringFileInfo\\{:04x}{:04x}\\FileDescription",
+            translation.0, translation.1
+        ))?;
+        
+        // len counts UTF-16 chars including the terminator
+        let slice = std::slice::from_raw_parts(value as *const u16, len as usize);
+        let description = String::from_utf16_lossy(slice)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        
+        if description.is_empty() { None } else { Some(description) }
+    }
+}
+
+/// Helper to get a human-friendly app name for a process: FileDescription, else exe name
+#[cfg(target_os = "windows")]
+fn process_display_name(process_id: u32) -> Option<String> {
+    let path = process_image_path(process_id)?;
+    
+    file_description(&path).or_else(|| {
+        std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    })
+}
+
+/// Helper to map a session state to the string used in AudioSession
+#[cfg(target_os = "windows")]
+fn audio_session_state_name(state: AudioSessionState) -> &'static str {
+    match state.0 {
+        1 => "active",  // AudioSessionStateActive
+        2 => "expired", // AudioSessionStateExpired
+        _ => "inactive",
+    }
+}
+
+/// List audio sessions (apps playing audio). Expired sessions (the app closed its stream but
+/// the session lingers) are left out unless `include_expired` is true.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_audio_sessions(include_expired: Option<bool>) -> Result<Vec<AudioSession>, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        
+        let enumerator = device_enumerator()?;
+        
+        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
+            .map_err(|e| format!("Failed to get default audio endpoint: {}", e))?;
+        
+        // Get audio session manager
+        let session_manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get session manager: {}", e))?;
+        
+        // Get session enumerator
+        let session_enum: IAudioSessionEnumerator = session_manager.GetSessionEnumerator()
+            .map_err(|e| format!("Failed to get session enumerator: {}", e))?;
+        
+        let count = session_enum.GetCount()
+            .map_err(|e| format!("Failed to get session count: {}", e))?;
+        
+        let mut sessions = Vec::new();
+        
+        for i in 0..count {
+            let session: IAudioSessionControl = match session_enum.GetSession(i) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            
+            // Get session control2 for more info
+            let session2: IAudioSessionControl2 = match session.cast() {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            
+            // Get process ID
+            let process_id = match session2.GetProcessId() {
+                Ok(pid) => pid,
+                Err(_) => continue,
+            };
+            
+            // Skip system sounds (process ID 0)
+            if process_id == 0 {
+                continue;
+            }
+            
+            // Get session state
+            let state = session.GetState().unwrap_or(AudioSessionState(0));
+            let is_active = state == AudioSessionState(1); // AudioSessionStateActive = 1
+            if state == AudioSessionState(2) && !include_expired.unwrap_or(false) {
+                continue;
+            }
+            
+            // Get display name (or process name as fallback)
+            let display_name = session.GetDisplayName()
+                .map(|s| {
+                    let len = (0..).take_while(|&i| *s.0.add(i) != 0).count();
+                    let slice = std::slice::from_raw_parts(s.0, len);
+                    let name = String::from_utf16_lossy(slice);
+                    windows::Win32::System::Com::CoTaskMemFree(Some(s.0 as *const _));
+                    name
+                })
+                .unwrap_or_default();
+            
+            // Without a display name, ask the process (fails for elevated apps),
+            // then fall back to the session identifier
+            let app_name = if display_name.is_empty() || display_name.starts_with("@{") {
+                if let Some(name) = process_display_name(process_id) {
+                    name
+                } else {
+                    session2.GetSessionIdentifier()
+                        .map(|s| {
+                            let len = (0..).take_while(|&i| *s.0.add(i) != 0).count();
+                            let slice = std::slice::from_raw_parts(s.0, len);
+                            let id = String::from_utf16_lossy(slice);
+                            windows::Win32::System::Com::CoTaskMemFree(Some(s.0 as *const _));
+                            // Extract app name from session ID (usually contains exe path)
+                            id.split('\\')
+                                .last()
+                                .map(|n| n.split('|').next().unwrap_or(n))
+                                .map(|n| n.trim_end_matches(".exe").to_string())
+                                .unwrap_or_else(|| format!("App {}", process_id))
+                        })
+                        .unwrap_or_else(|_| format!("App {}", process_id))
+                }
+            } else {
+                display_name
+            };
+            
+            // Get volume interface
+            let volume: ISimpleAudioVolume = match session.cast() {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            
+            let level = volume.GetMasterVolume().unwrap_or(1.0);
+            let is_muted = volume.GetMute().map(|m| m.as_bool()).unwrap_or(false);
+            
+            // Stable per session; falls back to the PID if the instance ID is unavailable
+            let session_id = session2.GetSessionInstanceIdentifier()
+                .map(take_co_task_string)
+                .unwrap_or_else(|_| format!("{}", process_id));
+            
+            sessions.push(AudioSession {
+                session_id,
+                app_name,
+                process_id,
+                volume: level,
+                is_muted,
+                is_active,
+                state: audio_session_state_name(state).to_string(),
+                peak: read_session_peak(&session),
+            });
+        }
+        
+        // Sort by active status (active first), then by name
+        sessions.sort_by(|a, b| {
+            match (a.is_active, b.is_active) {
+                (true, false) => std::cmp::Ordering::Less,
+                (false, true) => std::cmp::Ordering::Greater,
+                _ => a.app_name.to_lowercase().cmp(&b.app_name.to_lowercase()),
+            }
+        });
+        
+        Ok(sessions)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn list_audio_sessions(_include_expired: Option<bool>) -> Result<Vec<AudioSession>, String> {
+    Ok(Vec::new())
+}
+
+/// Helper to find a session by instance identifier (AudioSession.session_id) or, failing
+/// that, the first session of a process. The instance ID tells apart several sessions of one PID.
+#[cfg(target_os = "windows")]
+fn find_audio_session(process_id: Option<u32>, session_id: Option<&str>) -> Result<IAudioSessionControl2, String> {
+    if process_id.is_none() && session_id.is_none() {
+        return Err("Either process_id or session_id is required".to_string());
+    }
+    
+    enumerate_audio_sessions()?
+        .into_iter()
+        .find(|session2| unsafe {
+            match session_id {
+                Some(id) => session2.GetSessionInstanceIdentifier()
+                    .map(|instance| take_co_task_string(instance) == id)
+                    .unwrap_or(false),
+                None => session2.GetProcessId().ok() == process_id,
+            }
+        })
+        .ok_or_else(|| match session_id {
+            Some(id) => format!("Session not found: {}", id),
+            None => format!("Session not found for process ID {}", process_id.unwrap_or(0)),
+        })
+}
+
+/// Set volume for a specific audio session, selected by `session_id` or `process_id`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_session_volume(process_id: Option<u32>, level: f32, session_id: Option<String>) -> Result<(), String> {
+    if level < 0.0 || level > 1.0 {
+        return Err("Volume level must be 0.0 to 1.0".to_string());
+    }
+    
+    let session2 = find_audio_session(process_id, session_id.as_deref())?;
+    
+    unsafe {
+        let volume: ISimpleAudioVolume = session2.cast()
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+        
+        volume.SetMasterVolume(level, std::ptr::null())
+            .map_err(|e| format!("Failed to set volume: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_session_volume(_process_id: Option<u32>, _level: f32, _session_id: Option<String>) -> Result<(), String> {
+    Err("Per-app volume not supported on this platform".to_string())
+}
+
+/// Mute/unmute a specific audio session, selected by `session_id` or `process_id`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_session_mute(process_id: Option<u32>, muted: bool, session_id: Option<String>) -> Result<(), String> {
+    let session2 = find_audio_session(process_id, session_id.as_deref())?;
+    
+    unsafe {
+        let volume: ISimpleAudioVolume = session2.cast()
+            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
+        
+        volume.SetMute(muted, std::ptr::null())
+            .map_err(|e| format!("Failed to set mute: {}", e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_session_mute(_process_id: Option<u32>, _muted: bool, _session_id: Option<String>) -> Result<(), String> {
+    Err("Per-app mute not supported on this platform".to_string())
+}
+
+/// Helper to list every audio session on the default render endpoint
+#[cfg(target_os = "windows")]
+fn enumerate_audio_sessions() -> Result<Vec<IAudioSessionControl2>, String> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let enumerator = device_enumerator()?;
+
+        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
+            .map_err(|e| format!("Failed to get default audio endpoint: {}", e))?;
+
+        let session_manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Failed to get session manager: {}", e))?;
+
+        let session_enum: IAudioSessionEnumerator = session_manager.GetSessionEnumerator()
+            .map_err(|e| format!("Failed to get session enumerator: {}", e))?;
+
+        let count = session_enum.GetCount()
+            .map_err(|e| format!("Failed to get session count: {}", e))?;
+
+        Ok((0..count)
+            .filter_map(|i| session_enum.GetSession(i).ok())
+            .filter_map(|session| session.cast::<IAudioSessionControl2>().ok())
+            .collect())
+    }
+}
+
+/// Helper to copy a CoTaskMem-allocated PWSTR into a String and free it
+#[cfg(target_os = "windows")]
+fn take_co_task_string(value: windows::core::PWSTR) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    unsafe {
+        let text = value.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(value.0 as *const _));
+        text
+    }
+}
+
+/// Helper to read a session's current peak level (0.0 - 1.0); 0 if the session has no meter
+#[cfg(target_os = "windows")]
+fn read_session_peak<T: windows::core::Interface>(session: &T) -> f32 {
+    session.cast::<IAudioMeterInformation>()
+        .and_then(|meter| unsafe { meter.GetPeakValue() })
+        .unwrap_or(0.0)
+}
+
+/// Get current peak levels as (process_id, peak), for per-app meters polled at a high rate.
+/// Processes with several sessions report their loudest one.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_session_peaks() -> Result<Vec<(u32, f32)>, String> {
+    let mut peaks: Vec<(u32, f32)> = Vec::new();
+    
+    for session2 in enumerate_audio_sessions()? {
+        let process_id = match unsafe { session2.GetProcessId() } {
+            Ok(pid) if pid != 0 => pid,
+            _ => continue,
+        };
+        let peak = read_session_peak(&session2);
+        
+        match peaks.iter_mut().find(|(pid, _)| *pid == process_id) {
+            Some(entry) => entry.1 = entry.1.max(peak),
+            None => peaks.push((process_id, peak)),
+        }
+    }
+    
+    Ok(peaks)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_session_peaks() -> Result<Vec<(u32, f32)>, String> {
+    Ok(Vec::new())
+}
+
+/// Mute states from before solo_audio_session, keyed by session instance identifier
+/// (a process can own several sessions). Empty when nothing is soloed.
+#[cfg(target_os = "windows")]
+static SOLO_PRIOR_MUTES: Lazy<std::sync::Mutex<std::collections::HashMap<String, bool>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+/// Mute every session except `process_id`'s (e.g. everything but the game), unmuting the target.
+/// Soloing again while soloed keeps the original states, so unsolo_audio_sessions restores
+/// what the user had before the first solo.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn solo_audio_session(process_id: u32) -> Result<(), String> {
+    let sessions = enumerate_audio_sessions()?;
+    
+    let target_found = sessions.iter()
+        .any(|session2| unsafe { session2.GetProcessId() }.map(|pid| pid == process_id).unwrap_or(false));
+    if !target_found {
+        return Err(format!("Session not found for process ID {}", process_id));
+    }
+    
+    let mut prior = SOLO_PRIOR_MUTES.lock().unwrap_or_else(|e| e.into_inner());
+    
+    for session2 in sessions {
+        let pid = match unsafe { session2.GetProcessId() } {
+            Ok(pid) => pid,
+            Err(_) => continue,
+        };
+        // System sounds stay as they are
+        if pid == 0 {
+            continue;
+        }
+        
+        let volume: ISimpleAudioVolume = match session2.cast() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        // Without an instance ID the state couldn't be restored, so leave it alone
+        let instance_id = match unsafe { session2.GetSessionInstanceIdentifier() } {
+            Ok(id) => take_co_task_string(id),
+            Err(_) => continue,
+        };
+        
+        unsafe {
+            let is_muted = volume.GetMute().map(|m| m.as_bool()).unwrap_or(false);
+            prior.entry(instance_id).or_insert(is_muted);
+            
+            volume.SetMute(pid != process_id, std::ptr::null())
+                .map_err(|e| format!("Failed to set mute: {}", e))?;
+        }
+    }
+    
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn solo_audio_session(_process_id: u32) -> Result<(), String> {
+    Err("Per-app mute not supported on this platform".to_string())
+}
+
+/// Restore the mute states saved by solo_audio_session. Sessions that ended since are skipped.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn unsolo_audio_sessions() -> Result<(), String> {
+    let mut prior = SOLO_PRIOR_MUTES.lock().unwrap_or_else(|e| e.into_inner());
+    if prior.is_empty() {
+        return Ok(());
+    }
+    
+    for session2 in enumerate_audio_sessions()? {
+        let instance_id = match unsafe { session2.GetSessionInstanceIdentifier() } {
+            Ok(id) => take_co_task_string(id),
+            Err(_) => continue,
+        };
+        let was_muted = match prior.get(&instance_id) {
+            Some(&muted) => muted,
+            None => continue,
+        };
+        
+        if let Ok(volume) = session2.cast::<ISimpleAudioVolume>() {
+            unsafe {
+                let _ = volume.SetMute(was_muted, std::ptr::null());
+            }
+        }
+    }
+    
+    prior.clear();
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn unsolo_audio_sessions() -> Result<(), String> {
+    Err("Per-app mute not supported on this platform".to_string())
+}
+
+/// Session icons as data URLs keyed by lowercased exe path; None = exe has no icon
+#[cfg(target_os = "windows")]
+static SESSION_ICON_CACHE: Lazy<std::sync::Mutex<std::collections::HashMap<String, Option<String>>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+/// Helper to read one bitmap as top-down BGRA8 rows
+#[cfg(target_os = "windows")]
+fn read_bitmap_bgra(bitmap: HBITMAP, width: i32, height: i32) -> Option<Vec<u8>> {
+    unsafe {
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height, // negative = top-down
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        
+        let hdc = GetDC(None);
+        let lines = GetDIBits(
+            hdc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut _),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+        ReleaseDC(None, hdc);
+        
+        if lines == 0 { None } else { Some(pixels) }
+    }
+}
+
+/// Helper to convert an HICON into (width, height, BGRA8 pixels with straight alpha)
+#[cfg(target_os = "windows")]
+fn icon_to_bgra(icon: HICON) -> Option<(u32, u32, Vec<u8>)> {
+    unsafe {
+        let mut info = ICONINFO::default();
+        GetIconInfo(icon, &mut info).ok()?;
+        
+        let result = (|| {
+            // Monochrome icons have no color bitmap; not worth rendering
+            if info.hbmColor.is_invalid() {
+                return None;
+            }
+            
+            let mut bitmap = BITMAP::default();
+            if GetObjectW(
+                info.hbmColor,
+                std::mem::size_of::<BITMAP>() as i32,
+                Some(&mut bitmap as *mut _ as *mut _),
+            ) == 0 {
+                return None;
+            }
+            let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+            
+            let mut pixels = read_bitmap_bgra(info.hbmColor, width, height)?;
+            
+            // Legacy icons carry no alpha; derive it from the AND mask (black = opaque)
+            if pixels.chunks_exact(4).all(|px| px[3] == 0) {
+                let mask = read_bitmap_bgra(info.hbmMask, width, height)?;
+                for (px, m) in pixels.chunks_exact_mut(4).zip(mask.chunks_exact(4)) {
+                    px[3] = if m[0] == 0 { 255 } else { 0 };
+                }
+            }
+            
+            Some((width as u32, height as u32, pixels))
+        })();
+        
+        let _ = DeleteObject(info.hbmColor);
+        let _ = DeleteObject(info.hbmMask);
+        result
+    }
+}
+
+/// Helper to encode BGRA8 pixels as PNG bytes with the WinRT imaging encoder
+#[cfg(target_os = "windows")]
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>, String> {
+    encode_png_with_limit(width, height, pixels, POLL_MAX_ITERS)
+}
+
+/// encode_png for large images, whose encode can outlast POLL_MAX_ITERS
+#[cfg(target_os = "windows")]
+fn encode_png_with_limit(width: u32, height: u32, pixels: &[u8], max_iters: usize) -> Result<Vec<u8>, String> {
+    let stream = InMemoryRandomAccessStream::new()
+        .map_err(|e| format!("Failed to create stream: {}", e))?;
+    
+    let encoder_id = BitmapEncoder::PngEncoderId()
+        .map_err(|e| format!("Failed to get PNG encoder: {}", e))?;
+    let encoder = poll_async_op(
+        BitmapEncoder::CreateAsync(encoder_id, &stream).map_err(|e| format!("Failed to create encoder: {}", e))?,
+        "image encoder",
+    )?;
+    
+    encoder.SetPixelData(BitmapPixelFormat::Bgra8, BitmapAlphaMode::Straight, width, height, 96.0, 96.0, pixels)
+        .map_err(|e| format!("Failed to set pixel data: {}", e))?;
+    poll_async_action(
+        encoder.FlushAsync().map_err(|e| format!("Failed to encode image: {}", e))?,
+        "image encoding",
+        max_iters,
+    )?;
+    
+    stream.Seek(0).map_err(|e| format!("Failed to rewind stream: {}", e))?;
+    read_stream_bytes(&stream.cast().map_err(|e| format!("Failed to read encoded image: {}", e))?)
+}
+
+/// Helper to extract an exe's large shell icon as PNG bytes; Ok(None) if it has none
+#[cfg(target_os = "windows")]
+fn extract_exe_icon_png(path: &str) -> Result<Option<Vec<u8>>, String> {
+    let icon = unsafe {
+        let mut file_info = SHFILEINFOW::default();
+        let found = SHGetFileInfoW(
+            &windows::core::HSTRING::from(path),
+            windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut file_info),
+            std::mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_ICON | SHGFI_LARGEICON,
+        );
+        if found == 0 || file_info.hIcon.is_invalid() {
+            return Ok(None);
+        }
+        file_info.hIcon
+    };
+    
+    let pixels = icon_to_bgra(icon);
+    unsafe {
+        let _ = DestroyIcon(icon);
+    }
+    
+    match pixels {
+        Some((width, height, pixels)) => encode_png(width, height, &pixels).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Get the app icon of an audio session's process as a PNG data URL.
+/// Ok(None) when the process can't be queried (elevated) or its exe has no icon.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_session_icon(process_id: u32) -> Result<Option<String>, String> {
+    let path = match process_image_path(process_id) {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+    let key = path.to_lowercase();
+    
+    if let Some(cached) = SESSION_ICON_CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return Ok(cached.clone());
+    }
+    
+    // Encoding failures aren't cached so a later call can retry
+    let icon = match extract_exe_icon_png(&path) {
+        Ok(png) => png.map(|bytes| {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+            format!("data:image/png;base64,{}", encoded)
+        }),
+        Err(e) => {
+            eprintln!("[PILLAR] Failed to extract icon for {}: {}", path, e);
+            return Ok(None);
+        }
+    };
+    
+    SESSION_ICON_CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(key, icon.clone());
+    Ok(icon)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_session_icon(_process_id: u32) -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+/// Whether an audio session identifier belongs to a media app AUMID.
+/// Session identifiers embed the exe path ("...|\Device\...\Spotify.exe%b{...}") or, for
+/// packaged apps, the package family name.
+#[cfg(target_os = "windows")]
+fn audio_session_matches_app(identifier: &str, app_id: &str) -> bool {
+    let identifier = identifier.to_lowercase();
+    let app_id = app_id.to_lowercase();
+    if app_id.is_empty() {
+        return false;
+    }
+
+    // Packaged app: "Microsoft.ZuneMusic_8wekyb3d8bbwe!Microsoft.ZuneMusic"
+    if let Some((family, _)) = app_id.split_once('!') {
+        return identifier.contains(family);
+    }
+
+    // Desktop app: "Spotify.exe", "Chrome", or a full exe path
+    let exe = app_id.rsplit('\\').next().unwrap_or(&app_id);
+    let exe = if exe.ends_with(".exe") { exe.to_string() } else { format!("{}.exe", exe) };
+    identifier.contains(&format!("\\{}", exe))
+}
+
+/// Get the audio session for a media app (MediaInfo.session_id) so a now-playing card can show
+/// a volume slider for exactly that player. Use the returned process_id with set_session_volume.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_media_session_volume(app_id: String) -> Result<AudioSession, String> {
+    let mut matches = Vec::new();
+    for session2 in enumerate_audio_sessions()? {
+        let identifier = unsafe { session2.GetSessionIdentifier() }
+            .map(take_co_task_string)
+            .unwrap_or_default();
+        if !audio_session_matches_app(&identifier, &app_id) {
+            continue;
+        }
+
+        let process_id = unsafe { session2.GetProcessId() }.unwrap_or(0);
+        let state = unsafe { session2.GetState() }.unwrap_or(AudioSessionState(0));
+        let is_active = state == AudioSessionState(1); // AudioSessionStateActive = 1
+        let volume: ISimpleAudioVolume = match session2.cast() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let (level, is_muted) = unsafe {
+            (
+                volume.GetMasterVolume().unwrap_or(1.0),
+                volume.GetMute().map(|m| m.as_bool()).unwrap_or(false),
+            )
+        };
+
+        let session_id = unsafe { session2.GetSessionInstanceIdentifier() }
+            .map(take_co_task_string)
+            .unwrap_or_else(|_| format!("{}", process_id));
+
+        matches.push(AudioSession {
+            session_id,
+            app_name: app_id.clone(),
+            process_id,
+            volume: level,
+            is_muted,
+            is_active,
+            state: audio_session_state_name(state).to_string(),
+            peak: read_session_peak(&session2),
+        });
+    }
+
+    // Browsers spread audio over several processes; prefer the one actually playing
+    matches.sort_by_key(|s| !s.is_active);
+    matches
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No audio session for {}", app_id))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_media_session_volume(_app_id: String) -> Result<AudioSession, String> {
+    Err("Per-app volume not supported on this platform".to_string())
+}
+
+/// Helper to get the exe file name ("Discord.exe") behind a session: from the process when it
+/// can be queried, else from the session identifier, which embeds the exe path
+#[cfg(target_os = "windows")]
+fn audio_session_exe_name(session2: &IAudioSessionControl2) -> Option<String> {
+    let process_id = unsafe { session2.GetProcessId() }.unwrap_or(0);
+    
+    let path = process_image_path(process_id).or_else(|| {
+        // "{0.0.0.00000000}.{...}|\Device\HarddiskVolume3\...\Discord.exe%b{...}"
+        unsafe { session2.GetSessionIdentifier() }
+            .map(take_co_task_string)
+            .ok()
+            .and_then(|id| id.split('|').nth(1).map(|p| p.split('%').next().unwrap_or(p).to_string()))
+    })?;
+    
+    path.rsplit('\\').next().map(|name| name.to_string()).filter(|name| !name.is_empty())
+}
+
+/// Set the volume (0.0 - 1.0) of every session of an executable, e.g. "Discord.exe" or "discord".
+/// Unlike PIDs the exe name survives restarts, so rules like "Discord at 80%" can be persisted.
+/// Returns how many sessions were changed (0 = the app isn't playing audio).
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_app_volume(exe_name: String, level: f32) -> Result<u32, String> {
+    if !(0.0..=1.0).contains(&level) {
+        return Err("Volume level must be 0.0 to 1.0".to_string());
+    }
+    
+    let target = exe_name.to_lowercase();
+    let target = target.trim_end_matches(".exe");
+    let mut affected = 0;
+    
+    for session2 in enumerate_audio_sessions()? {
+        let matches = audio_session_exe_name(&session2)
+            .map(|name| name.to_lowercase().trim_end_matches(".exe") == target)
+            .unwrap_or(false);
+        if !matches {
+            continue;
+        }
+        
+        let volume: ISimpleAudioVolume = match session2.cast() {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        unsafe {
+            volume.SetMasterVolume(level, std::ptr::null())
+                .map_err(|e| format!("Failed to set volume: {}", e))?;
+        }
+        affected += 1;
+    }
+    
+    Ok(affected)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_app_volume(_exe_name: String, _level: f32) -> Result<u32, String> {
+    Err("Per-app volume not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Audio Session Events
+// =============================================================================
+
+/// Per-session sink: volume/mute changes go out as "audio-session-updated" (so sliders follow
+/// the Windows mixer), state changes and disconnects as "audio-sessions-changed".
+#[cfg(target_os = "windows")]
+#[windows::core::implement(IAudioSessionEvents)]
+struct AudioSessionEventsSink {
+    app_handle: tauri::AppHandle,
+    session_id: String,
+    process_id: u32,
+    watch_id: u64,
+}
+
+#[cfg(target_os = "windows")]
+impl IAudioSessionEvents_Impl for AudioSessionEventsSink_Impl {
+    fn OnDisplayNameChanged(&self, _name: &windows::core::PCWSTR, _context: *const windows::core::GUID) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnIconPathChanged(&self, _path: &windows::core::PCWSTR, _context: *const windows::core::GUID) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnSimpleVolumeChanged(
+        &self,
+        new_volume: f32,
+        new_mute: windows::Win32::Foundation::BOOL,
+        _context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit("audio-session-updated", AudioSessionUpdate {
+            session_id: self.session_id.clone(),
+            process_id: self.process_id,
+            volume: new_volume,
+            is_muted: new_mute.as_bool(),
+        });
+        Ok(())
+    }
+
+    fn OnChannelVolumeChanged(
+        &self,
+        _channel_count: u32,
+        _volumes: *const f32,
+        _changed_channel: u32,
+        _context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnGroupingParamChanged(&self, _param: *const windows::core::GUID, _context: *const windows::core::GUID) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    // Active <-> inactive <-> expired; the mixer re-queries to update is_active / drop the row
+    fn OnStateChanged(&self, new_state: AudioSessionState) -> windows::core::Result<()> {
+        use tauri::Emitter;
+        if new_state == AudioSessionStateExpired {
+            unwatch_audio_session(self.watch_id);
+        }
+        let _ = self.app_handle.emit("audio-sessions-changed", "state");
+        Ok(())
+    }
+
+    fn OnSessionDisconnected(&self, _reason: AudioSessionDisconnectReason) -> windows::core::Result<()> {
+        use tauri::Emitter;
+        unwatch_audio_session(self.watch_id);
+        let _ = self.app_handle.emit("audio-sessions-changed", "disconnected");
+        Ok(())
+    }
+}
+
+/// Emits "audio-sessions-changed" ("created") when an app starts a new audio session,
+/// and starts watching that session's events
+#[cfg(target_os = "windows")]
+#[windows::core::implement(IAudioSessionNotification)]
+struct AudioSessionNotifier {
+    app_handle: tauri::AppHandle,
+}
+
+#[cfg(target_os = "windows")]
+impl IAudioSessionNotification_Impl for AudioSessionNotifier_Impl {
+    fn OnSessionCreated(&self, new_session: Option<&IAudioSessionControl>) -> windows::core::Result<()> {
+        use tauri::Emitter;
+
+        if let Some(session) = new_session {
+            watch_audio_session(&self.app_handle, session);
+        }
+
+        let _ = self.app_handle.emit("audio-sessions-changed", "created");
+        Ok(())
+    }
+}
+
+/// One session whose events we receive
+#[cfg(target_os = "windows")]
+struct WatchedAudioSession {
+    watch_id: u64,
+    session_id: String,
+    control: IAudioSessionControl,
+    sink: IAudioSessionEvents,
+}
+
+/// Keeps the session manager, notifier and per-session sinks alive. Covers the current default
+/// render device; a default-device change re-subscribes on the new one.
+#[cfg(target_os = "windows")]
+struct AudioSessionSubscriptions {
+    manager: IAudioSessionManager2,
+    notifier: IAudioSessionNotification,
+    sessions: Vec<WatchedAudioSession>,
+}
+
+#[cfg(target_os = "windows")]
+unsafe impl Send for AudioSessionSubscriptions {}
+
+#[cfg(target_os = "windows")]
+static AUDIO_SESSION_SUBSCRIPTIONS: Lazy<std::sync::Mutex<Option<AudioSessionSubscriptions>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Tells sinks apart so an expired session can find (and drop) its own entry
+#[cfg(target_os = "windows")]
+static NEXT_AUDIO_SESSION_WATCH_ID: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(target_os = "windows")]
+struct UnregisterQueue(Vec<(IAudioSessionControl, IAudioSessionEvents)>);
+
+#[cfg(target_os = "windows")]
+unsafe impl Send for UnregisterQueue {}
+
+/// Unregister sinks off the callback thread: the audio engine doesn't allow
+/// UnregisterAudioSessionNotification from inside an IAudioSessionEvents callback
+#[cfg(target_os = "windows")]
+fn unregister_audio_sessions(watched: Vec<WatchedAudioSession>) {
+    if watched.is_empty() {
+        return;
+    }
+    let queue = UnregisterQueue(watched.into_iter().map(|w| (w.control, w.sink)).collect());
+    thread::spawn(move || {
+        let _com = ComGuard::new();
+        let queue = queue; // Capture the Send wrapper, not its (non-Send) field
+        for (control, sink) in &queue.0 {
+            let _ = unsafe { control.UnregisterAudioSessionNotification(sink) };
+        }
+    });
+}
+
+/// Stop watching an expired or disconnected session
+#[cfg(target_os = "windows")]
+fn unwatch_audio_session(watch_id: u64) {
+    let removed = {
+        let mut guard = AUDIO_SESSION_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(subs) = guard.as_mut() else { return };
+        let Some(index) = subs.sessions.iter().position(|w| w.watch_id == watch_id) else { return };
+        subs.sessions.swap_remove(index)
+    };
+    unregister_audio_sessions(vec![removed]);
+}
+
+/// Register an events sink on one session, unless it is already watched or expired
+#[cfg(target_os = "windows")]
+fn watch_audio_session(app_handle: &tauri::AppHandle, session: &IAudioSessionControl) {
+    if unsafe { session.GetState() }.is_ok_and(|state| state == AudioSessionStateExpired) {
+        return;
+    }
+
+    let session2 = session.cast::<IAudioSessionControl2>().ok();
+    let process_id = session2.as_ref()
+        .and_then(|session2| unsafe { session2.GetProcessId() }.ok())
+        .unwrap_or(0);
+    // Same ID list_audio_sessions reports, so the mixer can patch the right row
+    let session_id = session2.as_ref()
+        .and_then(|session2| unsafe { session2.GetSessionInstanceIdentifier() }.ok())
+        .map(take_co_task_string)
+        .unwrap_or_else(|| format!("{}", process_id));
+
+    let mut guard = AUDIO_SESSION_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(subs) = guard.as_mut() else { return };
+    // Sessions created while subscribing show up both here and in the enumeration
+    if subs.sessions.iter().any(|w| w.session_id == session_id) {
+        return;
+    }
+
+    let watch_id = NEXT_AUDIO_SESSION_WATCH_ID.fetch_add(1, Ordering::Relaxed);
+    let sink: IAudioSessionEvents = AudioSessionEventsSink {
+        app_handle: app_handle.clone(),
+        session_id: session_id.clone(),
+        process_id,
+        watch_id,
+    }
+    .into();
+
+    if unsafe { session.RegisterAudioSessionNotification(&sink) }.is_ok() {
+        subs.sessions.push(WatchedAudioSession {
+            watch_id,
+            session_id,
+            control: session.clone(),
+            sink,
+        });
+    }
+}
+
+/// Subscribe to session creation on the default render device and to events of the
+/// sessions that already exist
+#[cfg(target_os = "windows")]
+fn subscribe_audio_sessions_changed(app_handle: &tauri::AppHandle) -> bool {
+    if AUDIO_SESSION_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner()).is_some() {
+        return true;
+    }
+
+    let manager: IAudioSessionManager2 = match default_render_device(eConsole)
+        .and_then(|device| unsafe { device.Activate(CLSCTX_ALL, None) }
+            .map_err(|e| format!("Failed to get session manager: {}", e)))
+    {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("[PILLAR] Audio session events unavailable: {}", e);
+            return false;
+        }
+    };
+
+    let notifier: IAudioSessionNotification = AudioSessionNotifier {
+        app_handle: app_handle.clone(),
+    }
+    .into();
+
+    // Published before registering so OnSessionCreated never runs without a place to put the session
+    *AUDIO_SESSION_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner()) = Some(AudioSessionSubscriptions {
+        manager: manager.clone(),
+        notifier: notifier.clone(),
+        sessions: Vec::new(),
+    });
+
+    if let Err(e) = unsafe { manager.RegisterSessionNotification(&notifier) } {
+        eprintln!("[PILLAR] Failed to subscribe to audio session changes: {:?}", e);
+        *AUDIO_SESSION_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        return false;
+    }
+
+    // Enumerating is also what makes the manager start delivering OnSessionCreated
+    if let Ok(session_enum) = unsafe { manager.GetSessionEnumerator() } {
+        let count = unsafe { session_enum.GetCount() }.unwrap_or(0);
+        for i in 0..count {
+            if let Ok(session) = unsafe { session_enum.GetSession(i) } {
+                watch_audio_session(app_handle, &session);
+            }
+        }
+    }
+
+    eprintln!("[PILLAR] Successfully subscribed to audio session changes");
+    true
+}
+
+/// Move the session subscriptions to the new default render device
+#[cfg(target_os = "windows")]
+fn resubscribe_audio_sessions(app_handle: &tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let old = AUDIO_SESSION_SUBSCRIPTIONS.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(old) = old {
+        let _ = unsafe { old.manager.UnregisterSessionNotification(&old.notifier) };
+        unregister_audio_sessions(old.sessions);
+    }
+
+    if subscribe_audio_sessions_changed(app_handle) {
+        let _ = app_handle.emit("audio-sessions-changed", "device");
+    }
+}
+
+// =============================================================================
+// Brightness Control Types
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrightnessInfo {
+    pub level: u32,       // 0-100
+    pub min: u32,         // minimum brightness level
+    pub max: u32,         // maximum brightness level
+    pub is_supported: bool,
+    pub backend: String,  // "wmi" | "ddcci" | "none" (DDC/CI can be unreliable on some monitors)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContrastInfo {
+    pub level: u32,       // 0-100
+    pub min: u32,         // minimum contrast level
+    pub max: u32,         // maximum contrast level
+    pub is_supported: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorInfo {
+    pub id: String,       // GDI device name, e.g. "\\.\DISPLAY1"
+    pub name: String,     // Physical monitor description from the driver
+    pub is_primary: bool,
+}
+
+/// Raw DDC/CI VCP feature value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VcpFeature {
+    pub code: u8,
+    pub current: u32,
+    pub maximum: u32,
+    pub is_momentary: bool, // Momentary codes (e.g. degauss) have no meaningful current value
+}
+
+/// Per-monitor outcome of set_all_monitors_brightness
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorBrightnessResult {
+    pub monitor_id: String,
+    pub backend: String,       // "wmi" | "ddcci" | "none"
+    pub success: bool,
+    pub error: Option<String>, // Why the monitor didn't respond
+}
+
+// =============================================================================
+// Brightness Control Commands
+// =============================================================================
+
+/// Helper to get the monitor of the foreground window (primary if there is none)
+#[cfg(target_os = "windows")]
+fn foreground_hmonitor() -> HMONITOR {
+    unsafe { MonitorFromWindow(GetForegroundWindow(), MONITOR_DEFAULTTOPRIMARY) }
+}
+
+/// Helper to list every display monitor on the desktop
+#[cfg(target_os = "windows")]
+fn enumerate_hmonitors() -> Vec<HMONITOR> {
+    unsafe extern "system" fn collect(
+        hmonitor: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut windows::Win32::Foundation::RECT,
+        data: LPARAM,
+    ) -> windows::Win32::Foundation::BOOL {
+        let monitors = &mut *(data.0 as *mut Vec<HMONITOR>);
+        monitors.push(hmonitor);
+        true.into()
+    }
+
+    let mut monitors: Vec<HMONITOR> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(None, None, Some(collect), LPARAM(&mut monitors as *mut _ as isize));
+    }
+    monitors
+}
+
+/// Helper to read (device name, is_primary) of a monitor
+#[cfg(target_os = "windows")]
+fn monitor_device_info(hmonitor: HMONITOR) -> Option<(String, bool)> {
+    unsafe {
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(hmonitor, &mut info as *mut _ as *mut MONITORINFO).as_bool() {
+            return None;
+        }
+
+        let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+        Some((
+            String::from_utf16_lossy(&info.szDevice[..len]),
+            info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        ))
+    }
+}
+
+/// Helper to find a monitor by MonitorInfo.id
+#[cfg(target_os = "windows")]
+fn find_hmonitor(monitor_id: &str) -> Result<HMONITOR, String> {
+    enumerate_hmonitors()
+        .into_iter()
+        .find(|&hmonitor| {
+            monitor_device_info(hmonitor)
+                .map(|(id, _)| id.eq_ignore_ascii_case(monitor_id))
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| format!("Monitor not found: {}", monitor_id))
+}
+
+/// Physical monitor handles behind one HMONITOR (more than one when mirrored)
+#[cfg(target_os = "windows")]
+struct PhysicalMonitors(Vec<PHYSICAL_MONITOR>);
+
+// The handles are plain dxva2 handles, usable from any thread
+#[cfg(target_os = "windows")]
+unsafe impl Send for PhysicalMonitors {}
+
+/// Opened physical monitors keyed by HMONITOR. Opening costs tens of ms per call, which made
+/// brightness slider drags lag, so handles stay open until WM_DISPLAYCHANGE invalidates them.
+#[cfg(target_os = "windows")]
+static PHYSICAL_MONITOR_CACHE: Lazy<std::sync::Mutex<std::collections::HashMap<isize, PhysicalMonitors>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+/// Physical monitor opens and destroys, counted so tests can check the cache
+#[cfg(all(test, target_os = "windows"))]
+static PHYSICAL_MONITOR_OPENS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(all(test, target_os = "windows"))]
+static PHYSICAL_MONITOR_DESTROYS: AtomicUsize = AtomicUsize::new(0);
+
+/// Set on WM_DISPLAYCHANGE; the next brightness call closes the cached handles. The window
+/// procedure only flips this so the UI thread never waits on the cache lock behind DDC/CI I/O.
+#[cfg(target_os = "windows")]
+static PHYSICAL_MONITORS_STALE: AtomicBool = AtomicBool::new(false);
+
+/// Mark every cached physical monitor handle stale (display configuration changed)
+#[cfg(target_os = "windows")]
+fn invalidate_physical_monitors() {
+    PHYSICAL_MONITORS_STALE.store(true, Ordering::SeqCst);
+}
+
+/// Close every cached physical monitor handle
+#[cfg(target_os = "windows")]
+fn close_physical_monitors(cache: &mut std::collections::HashMap<isize, PhysicalMonitors>) {
+    for (_, monitors) in cache.drain() {
+        #[cfg(test)]
+        PHYSICAL_MONITOR_DESTROYS.fetch_add(1, Ordering::SeqCst);
+        unsafe {
+            let _ = DestroyPhysicalMonitors(&monitors.0);
+        }
+    }
+}
+
+/// Helper to open the physical monitors behind an HMONITOR
+#[cfg(target_os = "windows")]
+fn open_physical_monitors(hmonitor: HMONITOR) -> Result<Vec<PHYSICAL_MONITOR>, String> {
+    #[cfg(test)]
+    PHYSICAL_MONITOR_OPENS.fetch_add(1, Ordering::SeqCst);
+
+    unsafe {
+        // Get number of physical monitors
+        let mut num_monitors: u32 = 0;
+        GetNumberOfPhysicalMonitorsFromHMONITOR(hmonitor, &mut num_monitors)
+            .map_err(|e| format!("Failed to get monitor count: {}", e))?;
+        
+        if num_monitors == 0 {
+            return Err("No physical monitors found".to_string());
+        }
+        
+        // Get physical monitor handles
+        let mut monitors = vec![PHYSICAL_MONITOR::default(); num_monitors as usize];
+        GetPhysicalMonitorsFromHMONITOR(hmonitor, &mut monitors)
+            .map_err(|e| format!("Failed to get physical monitors: {}", e))?;
+        
+        Ok(monitors)
+    }
+}
+
+/// Run `f` with the (cached) physical monitors behind an HMONITOR
+#[cfg(target_os = "windows")]
+fn with_physical_monitors<R>(
+    hmonitor: HMONITOR,
+    f: impl FnOnce(&[PHYSICAL_MONITOR]) -> R,
+) -> Result<R, String> {
+    use std::collections::hash_map::Entry;
+
+    let mut cache = PHYSICAL_MONITOR_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if PHYSICAL_MONITORS_STALE.swap(false, Ordering::SeqCst) {
+        close_physical_monitors(&mut cache);
+    }
+
+    let monitors = match cache.entry(hmonitor.0 as isize) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(PhysicalMonitors(open_physical_monitors(hmonitor)?)),
+    };
+    Ok(f(&monitors.0))
+}
+
+/// Scale a DDC/CI VCP reading to 0-100 within the monitor's range; None for an empty range
+#[cfg(target_os = "windows")]
+fn normalize_vcp(min: u32, current: u32, max: u32) -> Option<u32> {
+    (current.saturating_sub(min) * 100)
+        .checked_div(max.saturating_sub(min))
+        .map(|level| level.min(100))
+}
+
+/// Helper to read DDC/CI brightness of a monitor (its first physical monitor)
+#[cfg(target_os = "windows")]
+fn read_ddc_brightness(hmonitor: HMONITOR) -> Result<BrightnessInfo, String> {
+    let (result, min_brightness, current_brightness, max_brightness) = with_physical_monitors(hmonitor, |monitors| {
+        let mut min_brightness: u32 = 0;
+        let mut current_brightness: u32 = 0;
+        let mut max_brightness: u32 = 0;
+
+        let result = unsafe {
+            GetMonitorBrightness(
+                monitors[0].hPhysicalMonitor,
+                &mut min_brightness,
+                &mut current_brightness,
+                &mut max_brightness,
+            )
+        };
+        (result, min_brightness, current_brightness, max_brightness)
+    })?;
+
+    if result == 0 {
+        return Err("Failed to get brightness - DDC/CI may not be supported".to_string());
+    }
+
+    Ok(BrightnessInfo {
+        level: normalize_vcp(min_brightness, current_brightness, max_brightness).unwrap_or(100),
+        min: min_brightness,
+        max: max_brightness,
+        is_supported: true,
+        backend: "ddcci".to_string(),
+    })
+}
+
+/// One physical monitor's DDC/CI brightness (min, current, max), or why it couldn't be read
+#[cfg(target_os = "windows")]
+type DdcRange = Result<(u32, u32, u32), String>;
+
+/// Helper to read one physical monitor's DDC/CI brightness range.
+/// Without a valid range min/max are meaningless, so that is an error too.
+#[cfg(target_os = "windows")]
+fn read_ddc_range(monitor: &PHYSICAL_MONITOR) -> DdcRange {
+    let mut min_brightness: u32 = 0;
+    let mut current_brightness: u32 = 0;
+    let mut max_brightness: u32 = 0;
+
+    let result = unsafe {
+        GetMonitorBrightness(
+            monitor.hPhysicalMonitor,
+            &mut min_brightness,
+            &mut current_brightness,
+            &mut max_brightness,
+        )
+    };
+    if result == 0 {
+        return Err("Failed to read brightness range - DDC/CI may not be supported".to_string());
+    }
+    if max_brightness <= min_brightness {
+        return Err(format!(
+            "Monitor reported an invalid brightness range ({}-{})",
+            min_brightness, max_brightness
+        ));
+    }
+    Ok((min_brightness, current_brightness, max_brightness))
+}
+
+/// Helper to read the brightness range of every physical monitor behind an HMONITOR
+#[cfg(target_os = "windows")]
+fn read_ddc_ranges(hmonitor: HMONITOR) -> Result<Vec<DdcRange>, String> {
+    with_physical_monitors(hmonitor, |monitors| monitors.iter().map(read_ddc_range).collect())
+}
+
+/// Helper to set DDC/CI brightness (0-100) on the physical monitors behind an HMONITOR, using
+/// ranges from read_ddc_ranges (one per monitor) so each step is a single DDC/CI transaction
+#[cfg(target_os = "windows")]
+fn write_ddc_levels(
+    hmonitor: HMONITOR,
+    ranges: &[DdcRange],
+    level: u32,
+) -> Result<(), String> {
+    with_physical_monitors(hmonitor, |monitors| {
+        let mut last_error = None;
+        let mut any_set = false;
+
+        for (monitor, range) in monitors.iter().zip(ranges) {
+            let (min_brightness, _, max_brightness) = match range {
+                Ok(range) => *range,
+                Err(e) => {
+                    last_error = Some(e.clone());
+                    continue;
+                }
+            };
+
+            let actual_level = min_brightness + (level.min(100) * (max_brightness - min_brightness)) / 100;
+            if unsafe { SetMonitorBrightness(monitor.hPhysicalMonitor, actual_level) } != 0 {
+                any_set = true;
+            } else {
+                last_error = Some("Failed to set brightness - DDC/CI may not be supported".to_string());
+            }
+        }
+
+        match (any_set, last_error) {
+            (true, _) => Ok(()),
+            (false, Some(e)) => Err(e),
+            (false, None) => Err("No physical monitors found".to_string()),
+        }
+    })?
+}
+
+/// Helper to set DDC/CI brightness (0-100) on every physical monitor behind an HMONITOR
+#[cfg(target_os = "windows")]
+fn write_ddc_brightness(hmonitor: HMONITOR, level: u32) -> Result<(), String> {
+    write_ddc_levels(hmonitor, &read_ddc_ranges(hmonitor)?, level)
+}
+
+/// Helper to read the WMI brightness (laptop internal panel) via the brightness crate
+#[cfg(target_os = "windows")]
+fn read_wmi_brightness() -> Option<u32> {
+    brightness::blocking::brightness_devices()
+        .filter_map(|device| device.ok())
+        .find_map(|device| device.get().ok())
+        .map(|level| level.min(100))
+}
+
+/// Get system brightness: try WMI (laptops) first via brightness crate, then DDC/CI (external monitors)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_system_brightness() -> Result<BrightnessInfo, String> {
+    // 1. Try brightness crate first (WMI - works on laptop internal panels)
+    if let Some(level) = read_wmi_brightness() {
+        return Ok(BrightnessInfo {
+            level,
+            min: 0,
+            max: 100,
+            is_supported: true,
+            backend: "wmi".to_string(),
+        });
+    }
+
+    // 2. Fallback: DDC/CI for external monitors
+    match read_ddc_brightness(foreground_hmonitor()) {
+        Ok(info) => Ok(info),
+        // 3. Neither backend answered
+        Err(_) => Ok(BrightnessInfo {
+            level: 100,
+            min: 0,
+            max: 100,
+            is_supported: false,
+            backend: "none".to_string(),
+        }),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_system_brightness() -> Result<BrightnessInfo, String> {
+    Ok(BrightnessInfo {
+        level: 100,
+        min: 0,
+        max: 100,
+        is_supported: false,
+        backend: "none".to_string(),
+    })
+}
+
+/// Set system brightness (0-100): try WMI (laptops) first, then DDC/CI (external monitors)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_system_brightness(level: u32) -> Result<(), String> {
+    let level = level.min(100);
+
+    // 1. Try brightness crate first (WMI - works on laptop internal panels)
+    for device_result in brightness::blocking::brightness_devices() {
+        if let Ok(device) = device_result {
+            if device.set(level).is_ok() {
+                return Ok(());
+            }
+        }
+    }
+
+    // 2. Fallback: DDC/CI for external monitors
+    write_ddc_brightness(foreground_hmonitor(), level)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_system_brightness(_level: u32) -> Result<(), String> {
+    Err("Brightness control not supported on this platform".to_string())
+}
+
+/// Bumped by every brightness fade so an in-flight fade thread knows it has been superseded
+#[cfg(target_os = "windows")]
+static BRIGHTNESS_FADE_GENERATION: AtomicU64 = AtomicU64::new(0);
+#[cfg(target_os = "windows")]
+const BRIGHTNESS_FADE_WMI_STEP_MS: u64 = 16;
+/// DDC/CI writes take ~50ms each; stepping faster only queues up writes on the monitor
+#[cfg(target_os = "windows")]
+const BRIGHTNESS_FADE_DDC_STEP_MS: u64 = 50;
+
+/// Fade brightness to `target` (0-100) over `duration_ms` instead of jumping.
+/// Uses the same backend as set_system_brightness (WMI, else DDC/CI).
+/// Returns immediately; a newer fade cancels this one.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn fade_system_brightness(target: u32, duration_ms: u64) -> Result<(), String> {
+    let target = target.min(100);
+    let generation = BRIGHTNESS_FADE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    thread::spawn(move || {
+        let _com = ComGuard::new();
+
+        // Pick the backend once: a WMI panel that reports a level, else DDC/CI
+        let wmi_device = brightness::blocking::brightness_devices()
+            .filter_map(|device| device.ok())
+            .find(|device| device.get().is_ok());
+        let hmonitor = foreground_hmonitor();
+
+        // DDC/CI ranges are read once here; each step is then a single write per monitor
+        let mut ddc_ranges = Vec::new();
+        let (start, step_ms) = match &wmi_device {
+            Some(device) => (device.get().unwrap_or(target).min(100), BRIGHTNESS_FADE_WMI_STEP_MS),
+            None => {
+                ddc_ranges = match read_ddc_ranges(hmonitor) {
+                    Ok(ranges) => ranges,
+                    Err(e) => {
+                        eprintln!("[PILLAR] Brightness fade failed: {}", e);
+                        return;
+                    }
+                };
+                let Some(&(min, current, max)) = ddc_ranges.iter().find_map(|range| range.as_ref().ok()) else {
+                    eprintln!("[PILLAR] Brightness fade failed: no monitor reported a brightness range");
+                    return;
+                };
+                (normalize_vcp(min, current, max).unwrap_or(100), BRIGHTNESS_FADE_DDC_STEP_MS)
+            }
+        };
+        let set_level = |level: u32| match &wmi_device {
+            Some(device) => {
+                let _ = device.set(level);
+            }
+            None => {
+                let _ = write_ddc_levels(hmonitor, &ddc_ranges, level);
+            }
+        };
+
+        // No more steps than whole levels to cover, so slow DDC/CI monitors aren't flooded
+        let steps = (duration_ms / step_ms)
+            .min(start.abs_diff(target) as u64)
+            .max(1);
+
+        for step in 1..steps {
+            if BRIGHTNESS_FADE_GENERATION.load(Ordering::SeqCst) != generation {
+                return; // Superseded by a newer fade
+            }
+            let level = start as f64 + (target as f64 - start as f64) * (step as f64 / steps as f64);
+            set_level(level.round() as u32);
+            thread::sleep(Duration::from_millis(step_ms));
+        }
+
+        // Land exactly on target
+        if BRIGHTNESS_FADE_GENERATION.load(Ordering::SeqCst) == generation {
+            set_level(target);
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn fade_system_brightness(_target: u32, _duration_ms: u64) -> Result<(), String> {
+    Err("Brightness control not supported on this platform".to_string())
+}
+
+/// List display monitors for per-monitor brightness
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_monitors() -> Result<Vec<MonitorInfo>, String> {
+    let mut monitors = Vec::new();
+
+    for hmonitor in enumerate_hmonitors() {
+        let (id, is_primary) = match monitor_device_info(hmonitor) {
+            Some(info) => info,
+            None => continue,
+        };
+
+        let name = with_physical_monitors(hmonitor, |physical| {
+            let description = physical[0].szPhysicalMonitorDescription; // copy: the struct is packed
+            let len = description.iter().position(|&c| c == 0).unwrap_or(description.len());
+            String::from_utf16_lossy(&description[..len])
+        })
+        .ok()
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| id.clone());
+
+        monitors.push(MonitorInfo { id, name, is_primary });
+    }
+
+    Ok(monitors)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn list_monitors() -> Result<Vec<MonitorInfo>, String> {
+    Ok(Vec::new())
+}
+
+/// Get DDC/CI brightness of one monitor (MonitorInfo.id); is_supported is false without DDC/CI
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_monitor_brightness(monitor_id: String) -> Result<BrightnessInfo, String> {
+    let hmonitor = find_hmonitor(&monitor_id)?;
+    Ok(read_ddc_brightness(hmonitor).unwrap_or(BrightnessInfo {
+        level: 100,
+        min: 0,
+        max: 100,
+        is_supported: false,
+        backend: "none".to_string(),
+    }))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_monitor_brightness(_monitor_id: String) -> Result<BrightnessInfo, String> {
+    Ok(BrightnessInfo {
+        level: 100,
+        min: 0,
+        max: 100,
+        is_supported: false,
+        backend: "none".to_string(),
+    })
+}
+
+/// Set DDC/CI brightness (0-100) of one monitor (MonitorInfo.id)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_monitor_brightness(monitor_id: String, level: u32) -> Result<(), String> {
+    let hmonitor = find_hmonitor(&monitor_id)?;
+    write_ddc_brightness(hmonitor, level.min(100))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_monitor_brightness(_monitor_id: String, _level: u32) -> Result<(), String> {
+    Err("Brightness control not supported on this platform".to_string())
+}
+
+/// Set every monitor to the same brightness (0-100), like a master volume: the internal panel
+/// through WMI, external monitors through DDC/CI. Failures are reported per monitor.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_all_monitors_brightness(level: u32) -> Result<Vec<MonitorBrightnessResult>, String> {
+    let level = level.min(100);
+
+    // WMI devices by GDI device name, to match them up with HMONITORs
+    let wmi_devices: Vec<(String, brightness::blocking::BrightnessDevice)> =
+        brightness::blocking::brightness_devices()
+            .filter_map(|device| device.ok())
+            .filter_map(|device| device.device_name().ok().map(|name| (name, device)))
+            .collect();
+
+    let mut results = Vec::new();
+
+    for hmonitor in enumerate_hmonitors() {
+        let monitor_id = match monitor_device_info(hmonitor) {
+            Some((id, _)) => id,
+            None => continue,
+        };
+
+        let wmi_device = wmi_devices
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&monitor_id))
+            .map(|(_, device)| device);
+
+        if let Some(device) = wmi_device {
+            if device.set(level).is_ok() {
+                results.push(MonitorBrightnessResult {
+                    monitor_id,
+                    backend: "wmi".to_string(),
+                    success: true,
+                    error: None,
+                });
+                continue;
+            }
+        }
+
+        let result = match write_ddc_brightness(hmonitor, level) {
+            Ok(()) => MonitorBrightnessResult {
+                monitor_id,
+                backend: "ddcci".to_string(),
+                success: true,
+                error: None,
+            },
+            Err(e) => MonitorBrightnessResult {
+                monitor_id,
+                backend: "none".to_string(),
+                success: false,
+                error: Some(e),
+            },
+        };
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_all_monitors_brightness(_level: u32) -> Result<Vec<MonitorBrightnessResult>, String> {
+    Err("Brightness control not supported on this platform".to_string())
+}
+
+/// Read a raw DDC/CI VCP feature (e.g. 0x60 = input source, 0x10 = brightness) of a monitor.
+/// Codes the monitor doesn't implement come back as an error from the monitor.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn ddc_get_vcp(monitor_id: String, code: u8) -> Result<VcpFeature, String> {
+    let hmonitor = find_hmonitor(&monitor_id)?;
+
+    with_physical_monitors(hmonitor, |monitors| unsafe {
+        let mut code_type = Default::default();
+        let mut current: u32 = 0;
+        let mut maximum: u32 = 0;
+
+        if GetVCPFeatureAndVCPFeatureReply(
+            monitors[0].hPhysicalMonitor,
+            code,
+            Some(&mut code_type),
+            &mut current,
+            Some(&mut maximum),
+        ) == 0 {
+            return Err(format!(
+                "Failed to read VCP 0x{:02X}: {}",
+                code,
+                windows::core::Error::from_win32()
+            ));
+        }
+
+        Ok(VcpFeature {
+            code,
+            current,
+            maximum,
+            is_momentary: code_type == MC_MOMENTARY,
+        })
+    })?
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn ddc_get_vcp(_monitor_id: String, _code: u8) -> Result<VcpFeature, String> {
+    Err("DDC/CI not supported on this platform".to_string())
+}
+
+/// Write a raw DDC/CI VCP feature, e.g. code 0x60 with 0x0F (DisplayPort 1) / 0x11 (HDMI 1)
+/// to switch inputs. Values are monitor-specific; unsupported codes error from the monitor.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn ddc_set_vcp(monitor_id: String, code: u8, value: u32) -> Result<(), String> {
+    let hmonitor = find_hmonitor(&monitor_id)?;
+
+    with_physical_monitors(hmonitor, |monitors| unsafe {
+        if SetVCPFeature(monitors[0].hPhysicalMonitor, code, value) == 0 {
+            return Err(format!(
+                "Failed to write VCP 0x{:02X}: {}",
+                code,
+                windows::core::Error::from_win32()
+            ));
+        }
+        Ok(())
+    })?
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn ddc_set_vcp(_monitor_id: String, _code: u8, _value: u32) -> Result<(), String> {
+    Err("DDC/CI not supported on this platform".to_string())
+}
+
+/// Helper to pick a monitor by MonitorInfo.id, or the foreground window's monitor
+#[cfg(target_os = "windows")]
+fn monitor_or_foreground(monitor_id: Option<&str>) -> Result<HMONITOR, String> {
+    match monitor_id {
+        Some(id) => find_hmonitor(id),
+        None => Ok(foreground_hmonitor()),
+    }
+}
+
+/// Get DDC/CI contrast of a monitor (default: the foreground window's).
+/// Monitors without contrast support report is_supported: false instead of an error.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_monitor_contrast(monitor_id: Option<String>) -> Result<ContrastInfo, String> {
+    let unsupported = ContrastInfo {
+        level: 50,
+        min: 0,
+        max: 100,
+        is_supported: false,
+    };
+
+    let hmonitor = monitor_or_foreground(monitor_id.as_deref())?;
+    let reading = with_physical_monitors(hmonitor, |monitors| {
+        let mut min_contrast: u32 = 0;
+        let mut current_contrast: u32 = 0;
+        let mut max_contrast: u32 = 0;
+
+        let result = unsafe {
+            GetMonitorContrast(
+                monitors[0].hPhysicalMonitor,
+                &mut min_contrast,
+                &mut current_contrast,
+                &mut max_contrast,
+            )
+        };
+        (result != 0).then_some((min_contrast, current_contrast, max_contrast))
+    });
+
+    let (min_contrast, current_contrast, max_contrast) = match reading {
+        Ok(Some(values)) => values,
+        _ => return Ok(unsupported),
+    };
+
+    Ok(ContrastInfo {
+        level: normalize_vcp(min_contrast, current_contrast, max_contrast).unwrap_or(50),
+        min: min_contrast,
+        max: max_contrast,
+        is_supported: true,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_monitor_contrast(_monitor_id: Option<String>) -> Result<ContrastInfo, String> {
+    Ok(ContrastInfo {
+        level: 50,
+        min: 0,
+        max: 100,
+        is_supported: false,
+    })
+}
+
+/// Set DDC/CI contrast (0-100) of a monitor (default: the foreground window's)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_monitor_contrast(level: u32, monitor_id: Option<String>) -> Result<(), String> {
+    let hmonitor = monitor_or_foreground(monitor_id.as_deref())?;
+
+    let any_set = with_physical_monitors(hmonitor, |monitors| {
+        let mut any_set = false;
+        for monitor in monitors {
+            let mut min_contrast: u32 = 0;
+            let mut current_contrast: u32 = 0;
+            let mut max_contrast: u32 = 0;
+
+            unsafe {
+                if GetMonitorContrast(
+                    monitor.hPhysicalMonitor,
+                    &mut min_contrast,
+                    &mut current_contrast,
+                    &mut max_contrast,
+                ) == 0 || max_contrast <= min_contrast {
+                    continue; // No usable range; don't write a value derived from it
+                }
+
+                let range = max_contrast - min_contrast;
+                let actual_level = min_contrast + (level.min(100) * range) / 100;
+
+                if SetMonitorContrast(monitor.hPhysicalMonitor, actual_level) != 0 {
+                    any_set = true;
+                }
+            }
+        }
+        any_set
+    })?;
+
+    if any_set {
+        Ok(())
+    } else {
+        Err("Failed to set contrast - the monitor may not support it over DDC/CI".to_string())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_monitor_contrast(_level: u32, _monitor_id: Option<String>) -> Result<(), String> {
+    Err("Contrast control not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Brightness Events
+// =============================================================================
+
+#[cfg(target_os = "windows")]
+const BRIGHTNESS_POLL_MS: u64 = 1000;
+
+/// Emit "brightness-changed" (BrightnessInfo) when the panel brightness changes outside the
+/// island: Fn keys, battery auto-dim, adaptive brightness. The brightness crate has no event
+/// API, so this polls WMI once a second and only emits on change. DDC/CI monitors aren't
+/// polled (slow, and they don't change on their own), so the thread exits without a WMI panel.
+#[cfg(target_os = "windows")]
+fn watch_brightness_changes(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        use tauri::Emitter;
+
+        let _com = ComGuard::new();
+        let mut last = match read_wmi_brightness() {
+            Some(level) => level,
+            None => return,
+        };
+
+        loop {
+            thread::sleep(Duration::from_millis(BRIGHTNESS_POLL_MS));
+
+            let level = match read_wmi_brightness() {
+                Some(level) => level,
+                None => continue,
+            };
+            if level != last {
+                last = level;
+                let _ = app_handle.emit("brightness-changed", BrightnessInfo {
+                    level,
+                    min: 0,
+                    max: 100,
+                    is_supported: true,
+                    backend: "wmi".to_string(),
+                });
+            }
+        }
+    });
+}
+
+// =============================================================================
+// Color Temperature
+// =============================================================================
+
+#[cfg(target_os = "windows")]
+const COLOR_TEMPERATURE_MIN_K: u32 = 1000;
+#[cfg(target_os = "windows")]
+const COLOR_TEMPERATURE_MAX_K: u32 = 6500; // daylight = identity ramp
+
+/// Approximate RGB of a black body at `kelvin` as 0.0-1.0 channel factors
+/// (Tanner Helland's fit of the CIE data, good for 1000K-40000K)
+#[cfg(target_os = "windows")]
+fn kelvin_to_rgb(kelvin: u32) -> (f64, f64, f64) {
+    let t = kelvin as f64 / 100.0;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (t - 60.0).powf(-0.1332047592)
+    };
+    let g = if t <= 66.0 {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.0).powf(-0.0755148492)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (t - 10.0).ln() - 305.0447927307
+    };
+
+    (
+        r.clamp(0.0, 255.0) / 255.0,
+        g.clamp(0.0, 255.0) / 255.0,
+        b.clamp(0.0, 255.0) / 255.0,
+    )
+}
+
+/// Gamma ramp (R, G, B x 256 entries) that tints the screen to `kelvin`.
+/// Factors are relative to 6500K so the top of the range is exactly the identity ramp.
+#[cfg(target_os = "windows")]
+fn color_temperature_ramp(kelvin: u32) -> [[u16; 256]; 3] {
+    let (r, g, b) = kelvin_to_rgb(kelvin);
+    let (r0, g0, b0) = kelvin_to_rgb(COLOR_TEMPERATURE_MAX_K);
+    let factors = [
+        (r / r0).min(1.0),
+        (g / g0).min(1.0),
+        (b / b0).min(1.0),
+    ];
+
+    let mut ramp = [[0u16; 256]; 3];
+    for (channel, factor) in ramp.iter_mut().zip(factors) {
+        for (i, value) in channel.iter_mut().enumerate() {
+            *value = ((i as f64 * 257.0) * factor).round() as u16;
+        }
+    }
+    ramp
+}
+
+/// Helper to apply a gamma ramp to the primary display
+#[cfg(target_os = "windows")]
+fn apply_gamma_ramp(ramp: &[[u16; 256]; 3]) -> Result<(), String> {
+    unsafe {
+        let hdc = GetDC(None);
+        let ok = SetDeviceGammaRamp(hdc, ramp.as_ptr() as *const _).as_bool();
+        ReleaseDC(None, hdc);
+
+        if ok {
+            Ok(())
+        } else {
+            // Windows also refuses ramps far from identity (very low kelvin) by default
+            Err("Failed to set gamma ramp - the display driver may not support it".to_string())
+        }
+    }
+}
+
+/// Warm the screen to `kelvin` (clamped to 1000-6500; 6500 = neutral) with a gamma ramp.
+/// Independent of Windows Night light; some GPUs/drivers ignore gamma ramps and error here.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_color_temperature(kelvin: u32) -> Result<(), String> {
+    let kelvin = kelvin.clamp(COLOR_TEMPERATURE_MIN_K, COLOR_TEMPERATURE_MAX_K);
+    apply_gamma_ramp(&color_temperature_ramp(kelvin))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_color_temperature(_kelvin: u32) -> Result<(), String> {
+    Err("Color temperature not supported on this platform".to_string())
+}
+
+/// Restore the identity gamma ramp
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn reset_color_temperature() -> Result<(), String> {
+    apply_gamma_ramp(&color_temperature_ramp(COLOR_TEMPERATURE_MAX_K))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn reset_color_temperature() -> Result<(), String> {
+    Err("Color temperature not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Notification Commands
+// =============================================================================
+
+/// Helper to poll notification listener access.
+/// Updates the global cache on success.
+#[cfg(target_os = "windows")]
+fn poll_notification_access() -> Result<UserNotificationListenerAccessStatus, String> {
+    let listener = UserNotificationListener::Current()
+        .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+
+    let op = listener.RequestAccessAsync()
+        .map_err(|e| format!("Failed to request notification access: {}", e))?;
+
+    for _ in 0..POLL_MAX_ITERS {
+        let status = op.Status().map_err(|e| format!("Failed to get status: {}", e))?;
+        if status == AsyncStatus::Completed {
+            let result = op.GetResults().map_err(|e| format!("Failed to get results: {}", e))?;
+            NOTIFICATION_ACCESS_GRANTED.store(
+                result == UserNotificationListenerAccessStatus::Allowed,
+                Ordering::Relaxed,
+            );
+            return Ok(result);
+        }
+        if status == AsyncStatus::Error {
+            return Err("Async operation failed".to_string());
+        }
+        thread::sleep(Duration::from_millis(POLL_SLEEP_MS));
+    }
+    Err("Timeout waiting for notification access".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn poll_notification_access() -> Result<(), String> {
+    Err("Notifications not supported on this platform".to_string())
+}
+
+/// Helper to poll notifications list
+#[cfg(target_os = "windows")]
+fn poll_notifications_list(listener: &UserNotificationListener) -> Result<Vec<UserNotification>, String> {
+    let op = listener.GetNotificationsAsync(windows::UI::Notifications::NotificationKinds::Toast)
+        .map_err(|e| format!("Failed to get notifications: {}", e))?;
+
+    for _ in 0..POLL_MAX_ITERS {
+        let status = op.Status().map_err(|e| format!("Failed to get status: {}", e))?;
+        if status == AsyncStatus::Completed {
+            let notifs = op.GetResults()
+                .map_err(|e| format!("Failed to get results: {}", e))?;
+
+            let mut result = Vec::new();
+            let count = notifs.Size().unwrap_or(0);
+            for i in 0..count {
+                if let Ok(n) = notifs.GetAt(i) {
+                    result.push(n);
+                }
+            }
+            return Ok(result);
+        }
+        if status == AsyncStatus::Error {
+            return Err("Async operation failed".to_string());
+        }
+        thread::sleep(Duration::from_millis(POLL_SLEEP_MS));
+    }
+    Err("Timeout waiting for notifications".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn poll_notifications_list(_listener: &()) -> Result<Vec<()>, String> {
+    Err("Notifications not supported on this platform".to_string())
+}
+
+/// IDs of notifications still in the Windows list that the frontend already knows about.
+/// Intercepted ones are never recorded: we remove them from Windows ourselves.
+#[cfg(target_os = "windows")]
+static SEEN_NOTIFICATION_IDS: Lazy<std::sync::Mutex<std::collections::HashSet<u32>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashSet::new()));
+
+/// Diff the listener's list against the seen IDs: new notifications are intercepted
+/// (emitted as "notification-added", then dismissed from Windows to suppress the native
+/// banner); vanished ones are emitted as "notification-removed" with their ID.
+#[cfg(target_os = "windows")]
+fn emit_notification_diff(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    use tauri::Emitter;
+
+    let listener = UserNotificationListener::Current()
+        .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+    let notifications = poll_notifications_list(&listener)?;
+
+    let mut seen = SEEN_NOTIFICATION_IDS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut current = std::collections::HashSet::new();
+    let mut added = Vec::new();
+
+    for (idx, notif) in notifications.iter().enumerate() {
+        let Ok(id) = notif.Id() else { continue };
+        current.insert(id);
+        if seen.contains(&id) {
+            continue;
+        }
+
+        match extract_notification(notif, idx) {
+            // Blocked apps (and our own toasts) keep their native toast and stay out of the island
+            Some(sn) if !is_notification_app_blocked(&sn) && !is_own_notification(app_handle, &sn) => {
+                let _ = app_handle.emit("notification-added", &sn);
+                if listener.RemoveNotification(id).is_err() {
+                    seen.insert(id);
+                }
+                added.push(sn);
+            }
+            _ => {
+                seen.insert(id);
+            }
+        }
+    }
+
+    let removed: Vec<u32> = seen.iter().copied().filter(|id| !current.contains(id)).collect();
+    for id in removed {
+        seen.remove(&id);
+        let _ = app_handle.emit("notification-removed", id);
+    }
+    drop(seen);
+
+    if let Err(e) = append_notification_history(app_handle, &added) {
+        eprintln!("[PILLAR] {}", e);
+    }
+
+    Ok(())
+}
+
+/// Record what's already in the list so the first change event doesn't treat
+/// (and dismiss) the existing backlog as new.
+#[cfg(target_os = "windows")]
+fn seed_seen_notifications(listener: &UserNotificationListener) {
+    if let Ok(notifications) = poll_notifications_list(listener) {
+        let mut seen = SEEN_NOTIFICATION_IDS.lock().unwrap_or_else(|e| e.into_inner());
+        seen.extend(notifications.iter().filter_map(|n| n.Id().ok()));
+    }
+}
+
+/// Subscribe to Windows NotificationChanged with retry for transient startup races.
+/// Some systems return HRESULT 0x80070490 (Element not found) even when polling works.
+#[cfg(target_os = "windows")]
+fn subscribe_notification_changed(
+    listener: &UserNotificationListener,
+    app_handle: &tauri::AppHandle,
+) -> bool {
+    const RETRIES: usize = 3;
+    const RETRY_DELAY_MS: u64 = 500;
+    const E_ELEMENT_NOT_FOUND: i32 = 0x80070490u32 as i32;
+
+    seed_seen_notifications(listener);
+
+    for attempt in 1..=RETRIES {
+        let handle_for_event = app_handle.clone();
+        let handler = TypedEventHandler::new(
+            move |_listener: &Option<UserNotificationListener>,
+                  _args: &Option<UserNotificationChangedEventArgs>| {
+                use tauri::Emitter;
+
+                if let Err(e) = emit_notification_diff(&handle_for_event) {
+                    eprintln!("[PILLAR] {}", e);
+                    // Fallback: emit generic change event so the frontend re-fetches
+                    let _ = handle_for_event.emit("notification-changed", ());
+                }
+                Ok(())
+            },
+        );
+
+        match listener.NotificationChanged(&handler) {
+            Ok(_) => {
+                if attempt > 1 {
+                    eprintln!(
+                        "[PILLAR] Subscribed to NotificationChanged after retry {}",
+                        attempt
+                    );
+                } else {
+                    eprintln!("[PILLAR] Successfully subscribed to NotificationChanged");
+                }
+                return true;
+            }
+            Err(e) => {
+                let code = e.code().0;
+                let is_not_found = code == E_ELEMENT_NOT_FOUND;
+
+                // Retry only for the common startup race case.
+                if is_not_found && attempt < RETRIES {
+                    thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
+                    continue;
+                }
+
+                if is_not_found {
+                    eprintln!(
+                        "[PILLAR] NotificationChanged not available on this system; using polling fallback"
+                    );
+                } else {
+                    eprintln!("[PILLAR] Failed to subscribe to NotificationChanged: {:?}", e);
+                    eprintln!("[PILLAR] Notifications will still work via polling fallback");
+                }
+                return false;
+            }
+        }
+    }
+
+    false
+}
+
+/// Request notification access and check if granted.
+/// Also updates the cached access flag used by get_notifications().
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn check_notification_access() -> Result<bool, String> {
+    spawn_com_blocking(move || {
+        let status = poll_notification_access()?;
+        let allowed = status == UserNotificationListenerAccessStatus::Allowed;
+        NOTIFICATION_ACCESS_GRANTED.store(allowed, Ordering::Relaxed);
+        Ok(allowed)
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn check_notification_access() -> Result<bool, String> {
+    Ok(false)
+}
+
+/// Open the Settings page where notification access is granted, for a "Grant access"
+/// button shown when check_notification_access() returns false.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn open_notification_settings() -> Result<(), String> {
+    shell_open("ms-settings:privacy-notifications")
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn open_notification_settings() -> Result<(), String> {
+    Err("Notifications not supported on this platform".to_string())
+}
+
+/// Apps (AppUserModelId or display name, lowercased) whose notifications are hidden from the feed.
+static NOTIFICATION_APP_FILTER: Lazy<std::sync::Mutex<Vec<String>>> =
+    Lazy::new(|| std::sync::Mutex::new(Vec::new()));
+
+const NOTIFICATION_FILTER_FILE: &str = "notification_filter.json";
+
+/// Load the persisted blocklist into memory. A missing file just means no filter.
+fn load_notification_app_filter(app: &tauri::AppHandle) {
+    if let Some(blocked) = read_config_file::<Vec<String>>(app, NOTIFICATION_FILTER_FILE) {
+        *NOTIFICATION_APP_FILTER.lock().unwrap_or_else(|e| e.into_inner()) = blocked;
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_app_blocked(app_name: &str, aumid: Option<&str>) -> bool {
+    let blocked = NOTIFICATION_APP_FILTER.lock().unwrap_or_else(|e| e.into_inner());
+    if blocked.is_empty() {
+        return false;
+    }
+    let app_name = app_name.to_lowercase();
+    let aumid = aumid.map(str::to_lowercase);
+    blocked
+        .iter()
+        .any(|b| *b == app_name || aumid.as_deref() == Some(b.as_str()))
+}
+
+#[cfg(target_os = "windows")]
+fn is_notification_app_blocked(notification: &SystemNotification) -> bool {
+    is_app_blocked(&notification.app_name, notification.aumid.as_deref())
+}
+
+/// Our own AUMIDs (lowercased): the bundle identifier, which installed Tauri builds
+/// register as their AUMID, plus any explicit one set on the process.
+#[cfg(target_os = "windows")]
+static OWN_APP_IDS: once_cell::sync::OnceCell<Vec<String>> = once_cell::sync::OnceCell::new();
+
+#[cfg(target_os = "windows")]
+fn own_app_ids(app: &tauri::AppHandle) -> &'static [String] {
+    OWN_APP_IDS.get_or_init(|| {
+        let mut ids = vec![app.config().identifier.to_lowercase()];
+        unsafe {
+            if let Ok(explicit) = windows::Win32::UI::Shell::GetCurrentProcessExplicitAppUserModelID() {
+                let len = (0..).take_while(|&i| *explicit.0.add(i) != 0).count();
+                let id = String::from_utf16_lossy(std::slice::from_raw_parts(explicit.0, len)).to_lowercase();
+                windows::Win32::System::Com::CoTaskMemFree(Some(explicit.0 as *const _));
+                if !id.is_empty() && !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn is_own_aumid(app: &tauri::AppHandle, aumid: Option<&str>) -> bool {
+    aumid.is_some_and(|aumid| own_app_ids(app).iter().any(|id| id.eq_ignore_ascii_case(aumid)))
+}
+
+#[cfg(target_os = "windows")]
+fn is_own_notification(app: &tauri::AppHandle, notification: &SystemNotification) -> bool {
+    is_own_aumid(app, notification.aumid.as_deref())
+}
+
+/// Whether the feed hides notifications from this app: blocklisted, or (with `self_exclude`) ours
+#[cfg(target_os = "windows")]
+fn is_identity_hidden(app: &tauri::AppHandle, app_name: &str, aumid: Option<&str>, self_exclude: bool) -> bool {
+    is_app_blocked(app_name, aumid) || (self_exclude && is_own_aumid(app, aumid))
+}
+
+/// Get the list of apps whose notifications are hidden.
+#[tauri::command]
+fn get_notification_app_filter() -> Vec<String> {
+    NOTIFICATION_APP_FILTER.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Replace the notification blocklist. Entries match the AppUserModelId or the
+/// display name, case-insensitively. Persisted to the app config dir.
+#[tauri::command]
+fn set_notification_app_filter(app: tauri::AppHandle, blocked_apps: Vec<String>) -> Result<(), String> {
+    let mut blocked: Vec<String> = blocked_apps
+        .iter()
+        .map(|a| a.trim().to_lowercase())
+        .filter(|a| !a.is_empty())
+        .collect();
+    blocked.sort();
+    blocked.dedup();
+
+    write_config_file(&app, NOTIFICATION_FILTER_FILE, &blocked)?;
+
+    *NOTIFICATION_APP_FILTER.lock().unwrap_or_else(|e| e.into_inner()) = blocked;
+    Ok(())
+}
+
+/// Rolling on-disk feed, since Windows forgets notifications once they leave Action Center
+const NOTIFICATION_HISTORY_FILE: &str = "notification_history.json";
+const NOTIFICATION_HISTORY_CAP: usize = 500;
+
+/// In-memory copy of the history file (oldest first), loaded on first use
+static NOTIFICATION_HISTORY: Lazy<std::sync::Mutex<Option<Vec<SystemNotification>>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+fn read_notification_history(app: &tauri::AppHandle) -> Vec<SystemNotification> {
+    read_config_file(app, NOTIFICATION_HISTORY_FILE).unwrap_or_default()
+}
+
+/// Append newly seen notifications, dropping the oldest past the cap.
+/// Logos are stripped; they'd bloat the file and are re-read from the app anyway.
+#[cfg(target_os = "windows")]
+fn append_notification_history(app: &tauri::AppHandle, added: &[SystemNotification]) -> Result<(), String> {
+    if added.is_empty() {
+        return Ok(());
+    }
+
+    let mut history = NOTIFICATION_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+    let entries = history.get_or_insert_with(|| read_notification_history(app));
+
+    for sn in added {
+        if entries.iter().any(|e| e.id == sn.id && e.timestamp == sn.timestamp) {
+            continue;
+        }
+        entries.push(SystemNotification { app_logo: None, ..sn.clone() });
+    }
+    if entries.len() > NOTIFICATION_HISTORY_CAP {
+        let excess = entries.len() - NOTIFICATION_HISTORY_CAP;
+        entries.drain(..excess);
+    }
+
+    write_config_file(app, NOTIFICATION_HISTORY_FILE, entries)
+}
+
+/// Get persisted notifications, newest first. `limit` defaults to the whole history.
+#[tauri::command]
+fn get_notification_history(app: tauri::AppHandle, limit: Option<usize>) -> Vec<SystemNotification> {
+    let mut history = NOTIFICATION_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+    let entries = history.get_or_insert_with(|| read_notification_history(&app));
+
+    entries
+        .iter()
+        .rev()
+        .take(limit.unwrap_or(NOTIFICATION_HISTORY_CAP))
+        .cloned()
+        .collect()
+}
+
+/// Snoozed notifications by ID, tagged with the generation of the timer that owns them
+/// so re-snoozing replaces the earlier timer instead of firing twice.
+#[cfg(target_os = "windows")]
+static SNOOZED_NOTIFICATIONS: Lazy<std::sync::Mutex<std::collections::HashMap<u32, (u64, SystemNotification)>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+#[cfg(target_os = "windows")]
+static SNOOZE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Longest snooze accepted (a week); keeps `minutes * 60` far from overflowing
+#[cfg(target_os = "windows")]
+const MAX_SNOOZE_MINUTES: u64 = 7 * 24 * 60;
+
+/// Snooze a notification: it's dismissed now and re-emitted as "notification-snoozed-return"
+/// after `minutes`. Intercepted notifications are already gone from Windows, so the
+/// persisted history is searched too.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn snooze_notification(app: tauri::AppHandle, id: u32, minutes: u64) -> Result<(), String> {
+    spawn_com_blocking(move || {
+        if minutes == 0 {
+            return Err("Snooze duration must be at least 1 minute".to_string());
+        }
+        if minutes > MAX_SNOOZE_MINUTES {
+            return Err(format!("Snooze duration must be at most {} minutes", MAX_SNOOZE_MINUTES));
+        }
+
+        let listener = UserNotificationListener::Current()
+            .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+
+        let from_windows = poll_notifications_list(&listener)?
+            .iter()
+            .enumerate()
+            .find(|(_, n)| n.Id().ok() == Some(id))
+            .and_then(|(idx, n)| extract_notification(n, idx));
+
+        let notification = match from_windows {
+            Some(sn) => {
+                let _ = listener.RemoveNotification(id);
+                sn
+            }
+            None => {
+                let mut history = NOTIFICATION_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+                history
+                    .get_or_insert_with(|| read_notification_history(&app))
+                    .iter()
+                    .rev()
+                    .find(|sn| sn.id == id)
+                    .cloned()
+                    .ok_or_else(|| format!("Notification {} not found", id))?
+            }
+        };
+
+        let generation = SNOOZE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        SNOOZED_NOTIFICATIONS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, (generation, notification));
+
+        thread::spawn(move || {
+            use tauri::Emitter;
+
+            thread::sleep(Duration::from_secs(minutes * 60));
+
+            let mut snoozed = SNOOZED_NOTIFICATIONS.lock().unwrap_or_else(|e| e.into_inner());
+            if snoozed.get(&id).is_some_and(|(g, _)| *g == generation) {
+                if let Some((_, sn)) = snoozed.remove(&id) {
+                    let _ = app.emit("notification-snoozed-return", &sn);
+                }
+            }
+        });
+
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn snooze_notification(_app: tauri::AppHandle, _id: u32, _minutes: u64) -> Result<(), String> {
+    Err("Notifications not supported on this platform".to_string())
+}
+
+/// App logo data URLs keyed by AUMID (or display name). None is cached too, so
+/// apps without a logo aren't re-queried on every poll.
+#[cfg(target_os = "windows")]
+static NOTIFICATION_LOGO_CACHE: Lazy<std::sync::Mutex<std::collections::HashMap<String, Option<String>>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::HashMap::new()));
+
+/// Read the sender's logo from its AppDisplayInfo as a data URL.
+/// The listener API doesn't expose a toast's inline/hero images, only the app's own logo.
+#[cfg(target_os = "windows")]
+fn read_notification_app_logo(notif: &UserNotification) -> Result<Option<String>, String> {
+    let display_info = notif.AppInfo()
+        .and_then(|app_info| app_info.DisplayInfo())
+        .map_err(|e| format!("Failed to get app display info: {}", e))?;
+    let reference = match display_info.GetLogo(windows::Foundation::Size { Width: 48.0, Height: 48.0 }) {
+        Ok(r) => r,
+        Err(_) => return Ok(None),
+    };
+
+    let op = reference.OpenReadAsync()
+        .map_err(|e| format!("Failed to open app logo: {}", e))?;
+    let stream = poll_async_op(op, "app logo stream")?;
+    let bytes = read_stream_bytes(&stream.cast().map_err(|e| format!("Failed to read app logo: {}", e))?)?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+
+    let mime = sniff_image_mime(&bytes)
+        .map(|m| m.to_string())
+        .or_else(|| stream.ContentType().ok().map(|t| t.to_string()).filter(|t| t.starts_with("image/")))
+        .unwrap_or_else(|| "image/png".to_string());
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    Ok(Some(format!("data:{};base64,{}", mime, encoded)))
+}
+
+#[cfg(target_os = "windows")]
+fn notification_app_logo(notif: &UserNotification, key: &str) -> Option<String> {
+    if let Some(cached) = NOTIFICATION_LOGO_CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(key) {
+        return cached.clone();
+    }
+
+    match read_notification_app_logo(notif) {
+        Ok(logo) => {
+            NOTIFICATION_LOGO_CACHE
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key.to_string(), logo.clone());
+            logo
+        }
+        Err(e) => {
+            // Not cached, a later poll may succeed
+            eprintln!("[PILLAR] {}", e);
+            None
+        }
+    }
+}
+
+/// Display name and AUMID of the app that posted a notification
+#[cfg(target_os = "windows")]
+fn notification_app_identity(notif: &UserNotification) -> (String, Option<String>) {
+    let app_info = notif.AppInfo().ok();
+
+    let app_name = app_info
+        .as_ref()
+        .and_then(|app_info| app_info.DisplayInfo().ok())
+        .and_then(|display_info| display_info.DisplayName().ok())
+        .map(|h| h.to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Windows App".to_string());
+
+    let aumid = app_info
+        .as_ref()
+        .and_then(|app_info| app_info.AppUserModelId().ok())
+        .map(|h| h.to_string())
+        .filter(|s| !s.is_empty());
+
+    (app_name, aumid)
+}
+
+/// Extract a SystemNotification from a Windows UserNotification.
+/// Returns None if the notification has no meaningful content.
+/// Toast actions (Reply, Mark as read), the toast's scenario (incomingCall, alarm, ...)
+/// and the raw toast XML are not exposed by UserNotificationListener; only the visual
+/// text bindings are readable here.
+#[cfg(target_os = "windows")]
+fn extract_notification(notif: &UserNotification, idx: usize) -> Option<SystemNotification> {
+    let id = notif.Id().unwrap_or(idx as u32);
+
+    let (app_name, aumid) = notification_app_identity(notif);
+
+    let notification = notif.Notification().ok()?;
+    let visual = notification.Visual().ok()?;
+
+    let mut title = String::new();
+    let mut body = String::new();
+
+    if let Ok(bindings) = visual.Bindings() {
+        if let Ok(count) = bindings.Size() {
+            for i in 0..count {
+                if let Ok(binding) = bindings.GetAt(i) {
+                    if let Ok(elements) = binding.GetTextElements() {
+                        if let Ok(elem_count) = elements.Size() {
+                            for j in 0..elem_count {
+                                if let Ok(elem) = elements.GetAt(j) {
+                                    if let Ok(text) = elem.Text() {
+                                        let text_str = text.to_string();
+                                        if title.is_empty() {
+                                            title = text_str;
+                                        } else if body.is_empty() {
+                                            body = text_str;
+                                        } else {
+                                            body.push('\n');
+                                            body.push_str(&text_str);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    break; // Only process first binding
+                }
+            }
+        }
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let timestamp = notif
+        .CreationTime()
+        .ok()
+        .map(|dt| {
+            let ticks: i64 = dt.UniversalTime;
+            const EPOCH_OFFSET_100NS: i64 = 11644473600 * 10_000_000;
+            let unix_ms = ((ticks - EPOCH_OFFSET_100NS) / 10_000) as u64;
+            unix_ms
+        })
+        .filter(|&t| t > 0 && t < now + 86400_000)
+        .unwrap_or_else(|| now.saturating_sub(idx as u64 * 60000));
+
+    if title.is_empty() && body.is_empty() {
+        return None;
+    }
+
+    let app_logo = notification_app_logo(notif, aumid.as_deref().unwrap_or(&app_name));
+
+    Some(SystemNotification {
+        id,
+        app_name,
+        title,
+        body,
+        timestamp,
+        aumid,
+        app_logo,
+    })
+}
+
+#[cfg(target_os = "windows")]
+const DEFAULT_NOTIFICATION_LIMIT: usize = 10;
+
+/// Collapse notifications with the same app + title (chat apps re-post the same toast),
+/// keeping the most recent one in the slot of the first occurrence.
+#[cfg(target_os = "windows")]
+fn dedupe_notifications(notifications: Vec<SystemNotification>) -> Vec<SystemNotification> {
+    let mut slots: std::collections::HashMap<(String, String), usize> = std::collections::HashMap::new();
+    let mut result: Vec<SystemNotification> = Vec::with_capacity(notifications.len());
+
+    for sn in notifications {
+        let key = (sn.app_name.clone(), sn.title.clone());
+        match slots.get(&key) {
+            Some(&slot) => {
+                if sn.timestamp >= result[slot].timestamp {
+                    result[slot] = sn;
+                }
+            }
+            None => {
+                slots.insert(key, result.len());
+                result.push(sn);
+            }
+        }
+    }
+    result
+}
+
+/// Get recent notifications.
+/// Uses cached access status to avoid re-polling access on every call.
+/// `dedupe` collapses repeats of the same app + title into the latest one.
+/// `limit` defaults to 10; text extraction stops once it's reached.
+/// `self_exclude` (default true) hides PILLAR's own toasts.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn get_notifications(
+    app: tauri::AppHandle,
+    dedupe: Option<bool>,
+    limit: Option<usize>,
+    self_exclude: Option<bool>,
+) -> Result<Vec<SystemNotification>, String> {
+    spawn_com_blocking(move || {
+        if !NOTIFICATION_ACCESS_GRANTED.load(Ordering::Relaxed) {
+            return Ok(Vec::new());
+        }
+
+        let listener = UserNotificationListener::Current()
+            .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+
+        let notifications = poll_notifications_list(&listener)?;
+
+        let limit = limit.unwrap_or(DEFAULT_NOTIFICATION_LIMIT);
+        let self_exclude = self_exclude.unwrap_or(true);
+        let visible = notifications
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, notif)| extract_notification(notif, idx))
+            .filter(|sn| !is_notification_app_blocked(sn))
+            .filter(|sn| !(self_exclude && is_own_notification(&app, sn)));
+
+        // Deduping needs the whole list, otherwise stop extracting once the feed is full
+        let result: Vec<SystemNotification> = if dedupe.unwrap_or(false) {
+            let mut deduped = dedupe_notifications(visible.collect());
+            deduped.truncate(limit);
+            deduped
+        } else {
+            visible.take(limit).collect()
+        };
+
+        Ok(result)
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_notifications(
+    _dedupe: Option<bool>,
+    _limit: Option<usize>,
+    _self_exclude: Option<bool>,
+) -> Result<Vec<SystemNotification>, String> {
+    Ok(Vec::new())
+}
+
+/// Count pending notifications without reading their content (for badges).
+/// Blocked apps and, with `self_exclude` (default true), PILLAR's own toasts are excluded,
+/// matching the feed.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn get_notification_count(app: tauri::AppHandle, self_exclude: Option<bool>) -> Result<u32, String> {
+    spawn_com_blocking(move || {
+        if !NOTIFICATION_ACCESS_GRANTED.load(Ordering::Relaxed) {
+            return Ok(0);
+        }
+
+        let listener = UserNotificationListener::Current()
+            .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+
+        let notifications = poll_notifications_list(&listener)?;
+
+        let self_exclude = self_exclude.unwrap_or(true);
+        if !self_exclude && NOTIFICATION_APP_FILTER.lock().unwrap_or_else(|e| e.into_inner()).is_empty() {
+            return Ok(notifications.len() as u32);
+        }
+
+        // Only the app identity is read, never the visual content
+        let count = notifications
+            .iter()
+            .filter(|notif| {
+                let (app_name, aumid) = notification_app_identity(notif);
+                !is_identity_hidden(&app, &app_name, aumid.as_deref(), self_exclude)
+            })
+            .count();
+
+        Ok(count as u32)
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_notification_count(_self_exclude: Option<bool>) -> Result<u32, String> {
+    Ok(0)
+}
+
+/// Activate (bring to foreground) the app that created the notification with the given ID.
+/// Uses the same mechanism as Windows Action Center: the app is identified by its
+/// AppUserModelId (AUMID); we launch it via the shell (explorer shell:AppsFolder\AUMID)
+/// so both UWP and desktop apps (e.g. WhatsApp) are activated correctly.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn activate_notification(id: u32) -> Result<(), String> {
+    spawn_com_blocking(move || {
+        let listener = UserNotificationListener::Current()
+            .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+
+        let access = poll_notification_access()?;
+        if access != UserNotificationListenerAccessStatus::Allowed {
+            return Err("Notification access not granted".to_string());
+        }
+
+        let notifications = poll_notifications_list(&listener)?;
+        let notif = notifications
+            .iter()
+            .find(|n| n.Id().unwrap_or(0) == id)
+            .ok_or_else(|| format!("Notification {} not found", id))?;
+
+        let app_info = notif
+            .AppInfo()
+            .map_err(|e| format!("Failed to get app info: {}", e))?;
+
+        let aumid = app_info
+            .AppUserModelId()
+            .map_err(|e| format!("AppUserModelId not available: {}", e))?
+            .to_string();
+        if aumid.is_empty() {
+            return Err("AppUserModelId is empty".to_string());
+        }
+
+        // Allow the activated app to take foreground (same as when user clicks in Action Center).
+        unsafe {
+            let _ = AllowSetForegroundWindow(ASFW_ANY);
+        }
+
+        // Activate via shell:AppsFolder\{AUMID}. Try two methods:
+        // 1) Open the shell path directly (lpFile = "shell:AppsFolder\AUMID")
+        // 2) If that fails, run explorer.exe with the path as argument (for desktop apps)
+        let shell_path = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
+        let result = unsafe {
+            ShellExecuteW(
+                None,
+                &HSTRING::from("open"),
+                &shell_path,
+                None,
+                None,
+                SW_SHOWNORMAL,
+            )
+        };
+        if result.0 as isize > 32 {
+            return Ok(());
+        }
+        // Fallback: explorer.exe shell:AppsFolder\AUMID (some apps need this)
+        let explorer = HSTRING::from("explorer.exe");
+        let params = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
+        let result2 = unsafe {
+            ShellExecuteW(
+                None,
+                &HSTRING::from("open"),
+                &explorer,
+                &params,
+                None,
+                SW_SHOWNORMAL,
+            )
+        };
+        if result2.0 as isize <= 32 {
+            return Err(format!(
+                "Failed to activate app (ShellExecute returned {})",
+                result2.0 as isize
+            ));
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn activate_notification(_id: u32) -> Result<(), String> {
+    Err("Notification activation not supported on this platform".to_string())
+}
+
+/// Activate an app by its AUMID directly (used when notification was already dismissed from Windows).
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn activate_app_by_aumid(aumid: String) -> Result<(), String> {
+    if aumid.is_empty() {
+        return Err("AUMID is empty".to_string());
+    }
+
+    unsafe {
+        let _ = AllowSetForegroundWindow(ASFW_ANY);
+    }
+
+    let shell_path = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            &HSTRING::from("open"),
+            &shell_path,
+            None,
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    if result.0 as isize > 32 {
+        return Ok(());
+    }
+
+    let explorer = HSTRING::from("explorer.exe");
+    let params = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
+    let result2 = unsafe {
+        ShellExecuteW(
+            None,
+            &HSTRING::from("open"),
+            &explorer,
+            &params,
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    if result2.0 as isize <= 32 {
+        return Err(format!(
+            "Failed to activate app (ShellExecute returned {})",
+            result2.0 as isize
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn activate_app_by_aumid(_aumid: String) -> Result<(), String> {
+    Err("Not supported on this platform".to_string())
+}
+
+/// Dismiss a notification by ID
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn dismiss_notification(id: u32) -> Result<(), String> {
+    spawn_com_blocking(move || {
+        let listener = UserNotificationListener::Current()
+            .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+    
+        listener.RemoveNotification(id)
+            .map_err(|e| format!("Failed to dismiss notification: {}", e))
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn dismiss_notification(_id: u32) -> Result<(), String> {
+    Err("Notifications not supported on this platform".to_string())
+}
+
+/// Dismiss every notification in the feed. Keeps going past individual failures;
+/// returns how many were removed. Blocked apps and, with `self_exclude` (default true),
+/// PILLAR's own toasts are left alone since the feed never showed them.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn clear_all_notifications(app: tauri::AppHandle, self_exclude: Option<bool>) -> Result<u32, String> {
+    spawn_com_blocking(move || {
+        let self_exclude = self_exclude.unwrap_or(true);
+        let listener = UserNotificationListener::Current()
+            .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+
+        let notifications = poll_notifications_list(&listener)?;
+
+        let mut cleared = 0u32;
+        let mut failures: Vec<String> = Vec::new();
+        for notif in notifications.iter() {
+            let Ok(id) = notif.Id() else { continue };
+            // Only the app identity is read, never the visual content
+            let (app_name, aumid) = notification_app_identity(notif);
+            if is_identity_hidden(&app, &app_name, aumid.as_deref(), self_exclude) {
+                continue;
+            }
+            match listener.RemoveNotification(id) {
+                Ok(()) => cleared += 1,
+                Err(e) => failures.push(format!("{}: {}", id, e)),
+            }
+        }
+
+        if !failures.is_empty() {
+            eprintln!("[PILLAR] Failed to clear {} notification(s): {}", failures.len(), failures.join(", "));
+            if cleared == 0 {
+                return Err(format!("Failed to clear notifications: {}", failures.join(", ")));
+            }
+        }
+        Ok(cleared)
+    })
+    .await
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn get_media_session() -> Result<Option<MediaInfo>, String> {
-    Ok(None)
+fn clear_all_notifications(_self_exclude: Option<bool>) -> Result<u32, String> {
+    Err("Notifications not supported on this platform".to_string())
 }
 
-/// Play/pause media
+// Focus Assist publishes its active profile through WNF (Windows Notification Facility).
+// ntdll exports the query but it's not in the Win32 metadata; stable since Windows 10 1803.
+#[cfg(target_os = "windows")]
+#[link(name = "ntdll")]
+extern "system" {
+    fn NtQueryWnfStateData(
+        state_name: *const u64,
+        type_id: *const GUID,
+        explicit_scope: *const std::ffi::c_void,
+        change_stamp: *mut u32,
+        buffer: *mut std::ffi::c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+}
+
+/// WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u32 profile, 0 = off, 1 = priority only, 2 = alarms only
+#[cfg(target_os = "windows")]
+const WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u64 = 0x0D83_063E_A3BF_1C75;
+
+/// Get the Focus Assist (Do Not Disturb on Windows 11) state: "off" | "priority" | "alarms".
+/// Lets the frontend hold back notification popups while the user is focusing.
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn media_play_pause() -> Result<(), String> {
-    let session = get_current_session()?;
-    
-    let op = session.TryTogglePlayPauseAsync()
-        .map_err(|e| format!("Failed to toggle play/pause: {}", e))?;
-    
-    let _success = poll_bool_op(op)?;
-    Ok(())
+fn get_focus_assist_state() -> Result<String, String> {
+    let mut change_stamp = 0u32;
+    let mut profile = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+
+    let status = unsafe {
+        NtQueryWnfStateData(
+            &WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile as *mut u32 as *mut std::ffi::c_void,
+            &mut size,
+        )
+    };
+    if status < 0 {
+        return Err(format!("Failed to query Focus Assist state: NTSTATUS {:#010x}", status as u32));
+    }
+
+    // No data yet means Focus Assist has never been turned on this boot
+    if size == 0 {
+        return Ok("off".to_string());
+    }
+
+    match profile {
+        0 => Ok("off".to_string()),
+        1 => Ok("priority".to_string()),
+        2 => Ok("alarms".to_string()),
+        other => Err(format!("Unknown Focus Assist profile: {}", other)),
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn media_play_pause() -> Result<(), String> {
-    Err("Media controls not supported on this platform".to_string())
+fn get_focus_assist_state() -> Result<String, String> {
+    Ok("off".to_string())
+}
+// =============================================================================
+// Auto-Start Commands
+// =============================================================================
+
+/// Check if auto-start is enabled
+#[tauri::command]
+fn check_autostart_enabled(app: tauri::AppHandle) -> Result<bool, String> {
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_autostart::ManagerExt;
+        app.autolaunch()
+            .is_enabled()
+            .map_err(|e| format!("Failed to check autostart status: {}", e))
+    }
+    #[cfg(not(desktop))]
+    {
+        Ok(false)
+    }
 }
 
-/// Skip to next track
+/// Enable or disable auto-start
+#[tauri::command]
+fn set_autostart_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    #[cfg(desktop)]
+    {
+        use tauri_plugin_autostart::ManagerExt;
+        let autostart = app.autolaunch();
+        if enabled {
+            autostart.enable()
+                .map_err(|e| format!("Failed to enable autostart: {}", e))
+        } else {
+            autostart.disable()
+                .map_err(|e| format!("Failed to disable autostart: {}", e))
+        }
+    }
+    #[cfg(not(desktop))]
+    {
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Elevation Commands
+// =============================================================================
+
+/// Check whether PILLAR is running elevated (UAC admin token)
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn media_next() -> Result<(), String> {
-    let session = get_current_session()?;
-    
-    let op = session.TrySkipNextAsync()
-        .map_err(|e| format!("Failed to skip next: {}", e))?;
-    
-    let _success = poll_bool_op(op)?;
-    Ok(())
+fn is_elevated() -> Result<bool, String> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)
+            .map_err(|e| format!("Failed to open process token: {}", e))?;
+
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut returned: u32 = 0;
+        let result = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut _ as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+
+        let _ = CloseHandle(token);
+
+        result.map_err(|e| format!("Failed to query token elevation: {}", e))?;
+        Ok(elevation.TokenIsElevated != 0)
+    }
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn media_next() -> Result<(), String> {
-    Err("Media controls not supported on this platform".to_string())
+fn is_elevated() -> Result<bool, String> {
+    Ok(false)
 }
 
-/// Skip to previous track
+/// Restart PILLAR elevated via the "runas" verb (shows the UAC prompt).
+/// If the user cancels UAC we keep running unelevated and return an error the UI can ignore.
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn media_previous() -> Result<(), String> {
-    let session = get_current_session()?;
-    
-    let op = session.TrySkipPreviousAsync()
-        .map_err(|e| format!("Failed to skip previous: {}", e))?;
-    
-    let _success = poll_bool_op(op)?;
+fn relaunch_as_admin(app: tauri::AppHandle) -> Result<(), String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to resolve executable path: {}", e))?;
+
+    let args = std::env::args()
+        .skip(1)
+        .map(|a| format!("\"{}\"", a))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            &HSTRING::from("runas"),
+            &HSTRING::from(exe.as_os_str()),
+            &HSTRING::from(args),
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+
+    if result.0 as isize <= 32 {
+        let last_error = unsafe { GetLastError() };
+        if last_error == ERROR_CANCELLED {
+            return Err("Elevation cancelled by user".to_string());
+        }
+        return Err(format!(
+            "Failed to relaunch as administrator (ShellExecute returned {})",
+            result.0 as isize
+        ));
+    }
+
+    // The elevated instance is starting; hand over to it
+    app.exit(0);
     Ok(())
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn media_previous() -> Result<(), String> {
-    Err("Media controls not supported on this platform".to_string())
+fn relaunch_as_admin(_app: tauri::AppHandle) -> Result<(), String> {
+    Err("Elevation not supported on this platform".to_string())
 }
 
 // =============================================================================
-// Volume Control Commands
+// Registry Helpers
 // =============================================================================
 
-/// Get system volume
+/// Owned registry key handle, closed on drop so early returns don't leak it.
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn get_system_volume() -> Result<VolumeInfo, String> {
+struct RegKey(HKEY);
+
+#[cfg(target_os = "windows")]
+impl Drop for RegKey {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = RegCloseKey(self.0);
+        }
+    }
+}
+
+/// Helper to open a registry key with the requested access rights
+#[cfg(target_os = "windows")]
+fn open_reg_key(root: HKEY, path: &str, access: REG_SAM_FLAGS) -> Result<RegKey, WIN32_ERROR> {
+    let mut hkey = HKEY::default();
+    let result = unsafe { RegOpenKeyExW(root, &HSTRING::from(path), 0, access, &mut hkey) };
+    if result.is_ok() {
+        Ok(RegKey(hkey))
+    } else {
+        Err(result)
+    }
+}
+
+/// Helper to open a registry key, creating it when it doesn't exist yet
+#[cfg(target_os = "windows")]
+fn create_reg_key(root: HKEY, path: &str, access: REG_SAM_FLAGS) -> Result<RegKey, WIN32_ERROR> {
+    let mut hkey = HKEY::default();
+    let result = unsafe {
+        RegCreateKeyExW(
+            root,
+            &HSTRING::from(path),
+            0,
+            None,
+            REG_OPTION_NON_VOLATILE,
+            access,
+            None,
+            &mut hkey,
+            None,
+        )
+    };
+    if result.is_ok() {
+        Ok(RegKey(hkey))
+    } else {
+        Err(result)
+    }
+}
+
+/// Helper to read every value under a key as (name, raw bytes) pairs
+#[cfg(target_os = "windows")]
+fn read_reg_values(key: &RegKey) -> Vec<(String, Vec<u8>)> {
+    let mut values = Vec::new();
+    let mut index = 0;
+    let mut data = vec![0u8; 1024];
+
+    loop {
+        // Value names are capped at 16383 characters by the registry
+        let mut name_buf = [0u16; 16384];
+        let mut name_len = name_buf.len() as u32;
+        let mut data_len = data.len() as u32;
+
+        let result = unsafe {
+            RegEnumValueW(
+                key.0,
+                index,
+                windows::core::PWSTR(name_buf.as_mut_ptr()),
+                &mut name_len,
+                None,
+                None,
+                Some(data.as_mut_ptr()),
+                Some(&mut data_len),
+            )
+        };
+
+        if result == ERROR_MORE_DATA {
+            // Grow the data buffer and retry the same index
+            data.resize(data_len as usize, 0);
+            continue;
+        }
+        if result == ERROR_NO_MORE_ITEMS || result.is_err() {
+            break;
+        }
+
+        let name = String::from_utf16_lossy(&name_buf[..name_len as usize]);
+        values.push((name, data[..data_len as usize].to_vec()));
+        index += 1;
+    }
+
+    values
+}
+
+/// Helper to list the names of a key's direct subkeys
+#[cfg(target_os = "windows")]
+fn read_reg_subkeys(key: &RegKey) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut index = 0;
+
+    loop {
+        // Key names are capped at 255 characters by the registry
+        let mut name_buf = [0u16; 256];
+        let mut name_len = name_buf.len() as u32;
+
+        let result = unsafe {
+            RegEnumKeyExW(
+                key.0,
+                index,
+                windows::core::PWSTR(name_buf.as_mut_ptr()),
+                &mut name_len,
+                None,
+                windows::core::PWSTR::null(),
+                None,
+                None,
+            )
+        };
+        if result.is_err() {
+            break;
+        }
+
+        names.push(String::from_utf16_lossy(&name_buf[..name_len as usize]));
+        index += 1;
+    }
+
+    names
+}
+
+/// Decode a REG_SZ / REG_EXPAND_SZ payload (UTF-16LE, usually NUL-terminated)
+#[cfg(target_os = "windows")]
+fn reg_bytes_to_string(bytes: &[u8]) -> String {
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+    String::from_utf16_lossy(&wide)
+}
+
+/// Helper to read a REG_DWORD value. Returns None when the key or value is missing.
+#[cfg(target_os = "windows")]
+fn read_reg_dword(root: HKEY, path: &str, name: &str) -> Option<u32> {
+    let mut value: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            root,
+            &HSTRING::from(path),
+            &HSTRING::from(name),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut u32 as *mut _),
+            Some(&mut size),
+        )
+    };
+    if result.is_ok() { Some(value) } else { None }
+}
+
+/// Helper to read a REG_QWORD value. Returns None when the key or value is missing.
+#[cfg(target_os = "windows")]
+fn read_reg_qword(root: HKEY, path: &str, name: &str) -> Option<u64> {
+    let mut value: u64 = 0;
+    let mut size = std::mem::size_of::<u64>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            root,
+            &HSTRING::from(path),
+            &HSTRING::from(name),
+            RRF_RT_REG_QWORD,
+            None,
+            Some(&mut value as *mut u64 as *mut _),
+            Some(&mut size),
+        )
+    };
+    if result.is_ok() { Some(value) } else { None }
+}
+
+/// Helper to read a REG_BINARY value. Returns None when the key or value is missing.
+#[cfg(target_os = "windows")]
+fn read_reg_binary(root: HKEY, path: &str, name: &str) -> Option<Vec<u8>> {
+    let path = HSTRING::from(path);
+    let name = HSTRING::from(name);
     unsafe {
-        // Initialize COM
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        // Get device enumerator
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        // Get default audio endpoint
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| format!("Failed to get audio endpoint: {}", e))?;
-        
-        // Get volume interface
-        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
-            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
-        
-        // Get volume level (0.0 - 1.0)
-        let level = volume.GetMasterVolumeLevelScalar()
-            .map_err(|e| format!("Failed to get volume level: {}", e))?;
-        
-        // Get mute state
-        let is_muted = volume.GetMute()
-            .map_err(|e| format!("Failed to get mute state: {}", e))?
-            .as_bool();
-        
-        Ok(VolumeInfo {
-            level: (level * 100.0).round() as u32,
-            is_muted,
+        let mut size: u32 = 0;
+        if RegGetValueW(root, &path, &name, RRF_RT_REG_BINARY, None, None, Some(&mut size)).is_err() {
+            return None;
+        }
+
+        let mut data = vec![0u8; size as usize];
+        let result = RegGetValueW(
+            root,
+            &path,
+            &name,
+            RRF_RT_REG_BINARY,
+            None,
+            Some(data.as_mut_ptr() as *mut _),
+            Some(&mut size),
+        );
+        if result.is_err() {
+            return None;
+        }
+        data.truncate(size as usize);
+        Some(data)
+    }
+}
+
+/// Helper to write a REG_DWORD value into an existing key
+#[cfg(target_os = "windows")]
+fn write_reg_dword(root: HKEY, path: &str, name: &str, value: u32) -> Result<(), String> {
+    let key = open_reg_key(root, path, KEY_SET_VALUE)
+        .map_err(|e| format!("Failed to open registry key {}: {}", path, e.to_hresult()))?;
+    let result = unsafe {
+        RegSetValueExW(key.0, &HSTRING::from(name), 0, REG_DWORD, Some(&value.to_le_bytes()))
+    };
+    result
+        .ok()
+        .map_err(|e| format!("Failed to write registry value {}: {}", name, e))
+}
+
+// =============================================================================
+// Startup Apps Commands
+// =============================================================================
+
+/// Run keys scanned for startup entries, paired with the StartupApproved key
+/// Task Manager uses to record each entry's enabled state.
+#[cfg(target_os = "windows")]
+const STARTUP_SOURCES: [(&str, HKEY, &str, &str); 3] = [
+    (
+        "HKCU",
+        HKEY_CURRENT_USER,
+        "Software\\Microsoft\\Windows\\CurrentVersion\\Run",
+        "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved\\Run",
+    ),
+    (
+        "HKLM",
+        HKEY_LOCAL_MACHINE,
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Run",
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved\\Run",
+    ),
+    (
+        "HKLM32",
+        HKEY_LOCAL_MACHINE,
+        "SOFTWARE\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Run",
+        "SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\Explorer\\StartupApproved\\Run32",
+    ),
+];
+
+/// StartupApproved blobs are 12 bytes: a DWORD flag (even = enabled, odd = disabled)
+/// followed by the FILETIME the entry was disabled. A missing value means enabled.
+#[cfg(target_os = "windows")]
+fn is_startup_approved(blob: Option<&[u8]>) -> bool {
+    match blob {
+        Some(bytes) if !bytes.is_empty() => bytes[0] & 1 == 0,
+        _ => true,
+    }
+}
+
+/// List startup apps from the HKCU/HKLM Run keys with their Task Manager enabled state
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_startup_apps() -> Result<Vec<StartupApp>, String> {
+    let mut apps = Vec::new();
+
+    for (source, root, run_path, approved_path) in STARTUP_SOURCES {
+        let run_key = match open_reg_key(root, run_path, KEY_READ) {
+            Ok(k) => k,
+            Err(_) => continue, // e.g. no WOW6432Node on 32-bit systems
+        };
+
+        let approved = open_reg_key(root, approved_path, KEY_READ)
+            .map(|k| read_reg_values(&k))
+            .unwrap_or_default();
+
+        // Machine-wide entries can only be toggled when we can write HKLM
+        let read_only = root == HKEY_LOCAL_MACHINE
+            && open_reg_key(root, approved_path, KEY_SET_VALUE).is_err();
+
+        for (name, data) in read_reg_values(&run_key) {
+            let blob = approved
+                .iter()
+                .find(|(approved_name, _)| approved_name.eq_ignore_ascii_case(&name))
+                .map(|(_, bytes)| bytes.as_slice());
+
+            apps.push(StartupApp {
+                enabled: is_startup_approved(blob),
+                command: reg_bytes_to_string(&data),
+                name,
+                source: source.to_string(),
+                read_only,
+            });
+        }
+    }
+
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    Ok(apps)
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn list_startup_apps() -> Result<Vec<StartupApp>, String> {
+    Ok(Vec::new())
+}
+
+/// Enable or disable a startup app the same way Task Manager does (StartupApproved blob).
+/// The Run entry itself is never touched, so disabling is fully reversible.
+/// `source` (StartupApp.source) picks the entry when the same name is in several Run keys;
+/// without it the first one found is changed.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_startup_app_enabled(name: String, enabled: bool, source: Option<String>) -> Result<(), String> {
+    let (source, root, approved_path) = STARTUP_SOURCES
+        .iter()
+        .filter(|(candidate, ..)| source.as_deref().is_none_or(|s| s.eq_ignore_ascii_case(candidate)))
+        .find(|(_, root, run_path, _)| {
+            open_reg_key(*root, run_path, KEY_READ)
+                .map(|k| read_reg_values(&k).iter().any(|(n, _)| n.eq_ignore_ascii_case(&name)))
+                .unwrap_or(false)
         })
+        .map(|(source, root, _, approved_path)| (*source, *root, *approved_path))
+        .ok_or_else(|| format!("Startup app '{}' not found", name))?;
+
+    // StartupApproved\Run only exists once something has been toggled on this profile
+    let key = create_reg_key(root, approved_path, KEY_SET_VALUE).map_err(|e| {
+        if e == ERROR_ACCESS_DENIED {
+            format!("Administrator privileges required to change {} startup apps", source)
+        } else {
+            format!("Failed to open StartupApproved key: {}", e.to_hresult())
+        }
+    })?;
+
+    let mut blob = [0u8; 12];
+    if enabled {
+        blob[0] = 0x02;
+    } else {
+        blob[0] = 0x03;
+        // FILETIME (100ns ticks since 1601) of when the entry was disabled
+        const EPOCH_OFFSET_100NS: u64 = 11644473600 * 10_000_000;
+        let unix_100ns = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| (d.as_nanos() / 100) as u64)
+            .unwrap_or(0);
+        blob[4..].copy_from_slice(&(unix_100ns + EPOCH_OFFSET_100NS).to_le_bytes());
     }
+
+    let result = unsafe {
+        RegSetValueExW(key.0, &HSTRING::from(name.as_str()), 0, REG_BINARY, Some(&blob))
+    };
+    result
+        .ok()
+        .map_err(|e| format!("Failed to update startup state for {}: {}", name, e))
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn get_system_volume() -> Result<VolumeInfo, String> {
-    Ok(VolumeInfo { level: 0, is_muted: false })
+fn set_startup_app_enabled(_name: String, _enabled: bool, _source: Option<String>) -> Result<(), String> {
+    Err("Startup apps not supported on this platform".to_string())
 }
 
-/// Set system volume (0-100)
+// =============================================================================
+// Accessibility Commands
+// =============================================================================
+
+const CURSOR_SIZE_MIN: u32 = 32;
+#[cfg(target_os = "windows")]
+const CURSOR_SIZE_MAX: u32 = 256;
+#[cfg(target_os = "windows")]
+const CURSORS_KEY: &str = "Control Panel\\Cursors";
+
+/// Get the pointer size (CursorBaseSize, 32-256 px). Windows' default is 32.
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn set_system_volume(level: u32) -> Result<(), String> {
-    if level > 100 {
-        return Err("Volume level must be 0-100".to_string());
-    }
-    
-    unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| format!("Failed to get audio endpoint: {}", e))?;
-        
-        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
-            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
-        
-        volume.SetMasterVolumeLevelScalar(level as f32 / 100.0, std::ptr::null())
-            .map_err(|e| format!("Failed to set volume: {}", e))?;
-        
-        Ok(())
-    }
+fn get_cursor_size() -> Result<u32, String> {
+    let size = read_reg_dword(HKEY_CURRENT_USER, CURSORS_KEY, "CursorBaseSize")
+        .unwrap_or(CURSOR_SIZE_MIN);
+    Ok(size.clamp(CURSOR_SIZE_MIN, CURSOR_SIZE_MAX))
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn set_system_volume(_level: u32) -> Result<(), String> {
-    Err("Volume control not supported on this platform".to_string())
+fn get_cursor_size() -> Result<u32, String> {
+    Ok(CURSOR_SIZE_MIN)
 }
 
-/// Toggle mute
+/// Set the pointer size (clamped to 32-256) and reload cursors so it applies live.
+/// SPIF_SENDCHANGE broadcasts WM_SETTINGCHANGE so other apps pick it up too.
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn toggle_mute() -> Result<bool, String> {
+fn set_cursor_size(size: u32) -> Result<u32, String> {
+    let size = size.clamp(CURSOR_SIZE_MIN, CURSOR_SIZE_MAX);
+
+    write_reg_dword(HKEY_CURRENT_USER, CURSORS_KEY, "CursorBaseSize", size)?;
+
     unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| format!("Failed to get audio endpoint: {}", e))?;
-        
-        let volume: IAudioEndpointVolume = device.Activate(CLSCTX_ALL, None)
-            .map_err(|e| format!("Failed to get volume interface: {}", e))?;
-        
-        let is_muted = volume.GetMute()
-            .map_err(|e| format!("Failed to get mute state: {}", e))?
-            .as_bool();
-        
-        volume.SetMute(!is_muted, std::ptr::null())
-            .map_err(|e| format!("Failed to toggle mute: {}", e))?;
-        
-        Ok(!is_muted)
+        SystemParametersInfoW(SPI_SETCURSORS, 0, None, SPIF_UPDATEINIFILE | SPIF_SENDCHANGE)
+            .map_err(|e| format!("Failed to apply cursor size: {}", e))?;
     }
+
+    Ok(size)
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn toggle_mute() -> Result<bool, String> {
-    Err("Volume control not supported on this platform".to_string())
+fn set_cursor_size(_size: u32) -> Result<u32, String> {
+    Err("Cursor size not supported on this platform".to_string())
 }
 
 // =============================================================================
-// Audio Device Commands
+// Tablet Mode Commands
 // =============================================================================
 
-/// Helper to get device friendly name from IMMDevice using Windows Property Store
+/// Helper to read tablet posture. False on hardware that can't be a tablet.
 #[cfg(target_os = "windows")]
-fn get_device_name(device: &IMMDevice) -> Result<String, String> {
+fn read_tablet_mode() -> bool {
     unsafe {
-        // Open the property store for read access
-        let store: IPropertyStore = device.OpenPropertyStore(STGM_READ)
-            .map_err(|e| format!("Failed to open property store: {}", e))?;
-        
-        // Get the friendly name property
-        let value = store.GetValue(&PKEY_Device_FriendlyName)
-            .map_err(|e| format!("Failed to get device name property: {}", e))?;
-        
-        // Extract string from PROPVARIANT using Windows API (allocates; we must free)
-        if let Ok(pwstr) = PropVariantToStringAlloc(&value) {
-            if !pwstr.0.is_null() {
-                let len = (0..).take_while(|&i| *pwstr.0.add(i) != 0).count();
-                let slice = std::slice::from_raw_parts(pwstr.0, len);
-                let name = String::from_utf16_lossy(slice);
-                CoTaskMemFree(Some(pwstr.0 as *const _));
-                if !name.is_empty() {
-                    return Ok(name);
-                }
-            }
+        // SM_CONVERTIBLESLATEMODE reads 0 ("slate") on plenty of desktops, so only trust it
+        // on touch-capable laptops/tablets
+        let role = PowerDeterminePlatformRoleEx(POWER_PLATFORM_ROLE_V2);
+        let is_portable = role == PlatformRoleMobile || role == PlatformRoleSlate;
+        if !is_portable || GetSystemMetrics(SM_MAXIMUMTOUCHES) == 0 {
+            return false;
         }
-        
-        // Fallback: try to get a name from the device ID
-        let id = get_device_id(device)?;
-        let short_id = if id.len() > 8 { &id[id.len()-8..] } else { &id };
-        Ok(format!("Audio Device {}", short_id))
-    }
-}
 
-/// Helper to get device ID from IMMDevice
-#[cfg(target_os = "windows")]
-fn get_device_id(device: &IMMDevice) -> Result<String, String> {
-    unsafe {
-        let id = device.GetId()
-            .map_err(|e| format!("Failed to get device ID: {}", e))?;
-        
-        // Convert PWSTR to String
-        let len = (0..).take_while(|&i| *id.0.add(i) != 0).count();
-        let slice = std::slice::from_raw_parts(id.0, len);
-        let id_str = String::from_utf16_lossy(slice);
-        
-        // Free the string
-        windows::Win32::System::Com::CoTaskMemFree(Some(id.0 as *const _));
-        
-        Ok(id_str)
+        // Windows 10 tablet mode toggle (gone on Windows 11, where posture is automatic)
+        let tablet_mode = read_reg_dword(
+            HKEY_CURRENT_USER,
+            "Software\\Microsoft\\Windows\\CurrentVersion\\ImmersiveShell",
+            "TabletMode",
+        );
+        if tablet_mode == Some(1) {
+            return true;
+        }
+
+        // 0 = keyboard folded away / detached
+        GetSystemMetrics(SM_CONVERTIBLESLATEMODE) == 0
     }
 }
 
-/// List all audio output devices
+/// Get whether a 2-in-1 is in tablet posture. Changes are pushed as "posture-changed".
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
-    unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        // Get default device ID for comparison
-        let default_device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)
-            .map_err(|e| format!("Failed to get default device: {}", e))?;
-        let default_id = get_device_id(&default_device)?;
-        
-        // Enumerate all active render devices
-        let collection: IMMDeviceCollection = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
-            .map_err(|e| format!("Failed to enumerate devices: {}", e))?;
-        
-        let count = collection.GetCount()
-            .map_err(|e| format!("Failed to get device count: {}", e))?;
-        
-        let mut devices = Vec::new();
-        
-        for i in 0..count {
-            let device = collection.Item(i)
-                .map_err(|e| format!("Failed to get device {}: {}", i, e))?;
-            
-            let id = get_device_id(&device)?;
-            let name = get_device_name(&device).unwrap_or_else(|_| format!("Audio Device {}", i + 1));
-            let is_default = id == default_id;
-            
-            devices.push(AudioDevice {
-                id,
-                name,
-                is_default,
-            });
-        }
-        
-        Ok(devices)
-    }
+fn get_tablet_mode() -> Result<bool, String> {
+    Ok(read_tablet_mode())
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
-    Ok(Vec::new())
+fn get_tablet_mode() -> Result<bool, String> {
+    Ok(false)
 }
 
-/// Get the default audio device
+// =============================================================================
+// Display Topology Commands
+// =============================================================================
+
+/// Last topology reported, so topology-changed only fires on real projection switches
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn get_default_audio_device() -> Result<AudioDevice, String> {
-    unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eMultimedia)
-            .map_err(|e| format!("Failed to get default device: {}", e))?;
-        
-        let id = get_device_id(&device)?;
-        let name = get_device_name(&device)?;
-        
-        Ok(AudioDevice {
-            id,
-            name,
-            is_default: true,
-        })
+static LAST_DISPLAY_TOPOLOGY: Lazy<std::sync::Mutex<Option<String>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Helper to read the Win+P projection mode: "internal" | "external" | "extend" | "clone"
+#[cfg(target_os = "windows")]
+fn read_display_topology() -> Result<String, String> {
+    loop {
+        let mut path_count: u32 = 0;
+        let mut mode_count: u32 = 0;
+        unsafe { GetDisplayConfigBufferSizes(QDC_DATABASE_CURRENT, &mut path_count, &mut mode_count) }
+            .ok()
+            .map_err(|e| format!("Failed to get display config sizes: {}", e))?;
+
+        let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+        let mut modes = vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+        let mut topology = DISPLAYCONFIG_TOPOLOGY_ID::default();
+        let result = unsafe {
+            QueryDisplayConfig(
+                QDC_DATABASE_CURRENT,
+                &mut path_count,
+                paths.as_mut_ptr(),
+                &mut mode_count,
+                modes.as_mut_ptr(),
+                Some(&mut topology),
+            )
+        };
+
+        // A display was attached between the two calls; size the buffers again
+        if result == ERROR_INSUFFICIENT_BUFFER {
+            continue;
+        }
+        result
+            .ok()
+            .map_err(|e| format!("Failed to query display config: {}", e))?;
+
+        let name = match topology {
+            DISPLAYCONFIG_TOPOLOGY_INTERNAL => "internal",
+            DISPLAYCONFIG_TOPOLOGY_EXTERNAL => "external",
+            DISPLAYCONFIG_TOPOLOGY_CLONE => "clone",
+            DISPLAYCONFIG_TOPOLOGY_EXTEND => "extend",
+            other => return Err(format!("Unknown display topology: {}", other.0)),
+        };
+        return Ok(name.to_string());
     }
 }
 
+/// Get the current projection mode. In "clone" the displays show the same desktop and should be
+/// treated as one; in "extend" the island has to pick a monitor. Changes emit "topology-changed".
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_display_topology() -> Result<String, String> {
+    let topology = read_display_topology()?;
+    *LAST_DISPLAY_TOPOLOGY.lock().unwrap_or_else(|e| e.into_inner()) = Some(topology.clone());
+    Ok(topology)
+}
+
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn get_default_audio_device() -> Result<AudioDevice, String> {
-    Err("Audio devices not supported on this platform".to_string())
+fn get_display_topology() -> Result<String, String> {
+    Ok("internal".to_string())
 }
 
 // =============================================================================
-// Per-App Volume Commands
+// Power Plan Commands
 // =============================================================================
 
-/// List all audio sessions (apps playing audio)
+// The Settings "Power mode" slider is an overlay on top of the active plan. These powrprof
+// exports aren't in the Win32 metadata but have been stable since Windows 10 1709.
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn list_audio_sessions() -> Result<Vec<AudioSession>, String> {
+#[link(name = "powrprof")]
+extern "system" {
+    fn PowerGetActualOverlayScheme(actual_overlay_guid: *mut GUID) -> u32;
+    fn PowerSetActiveOverlayScheme(overlay_scheme_guid: *const GUID) -> u32;
+}
+
+/// Power mode slider positions and their overlay GUIDs ("balanced" is the nil GUID)
+#[cfg(target_os = "windows")]
+const POWER_MODES: [(&str, u128); 4] = [
+    ("best_efficiency", 0x961cc777_2547_4f9d_8174_7d86181b8a7a),
+    ("balanced", 0),
+    ("better_performance", 0x3af9b8d9_7c97_431d_ad78_34a8bfea439f),
+    ("best_performance", 0xded574b5_45a0_4f42_8737_46345c09c238),
+];
+
+/// Format a GUID the way powercfg prints it (lowercase, no braces)
+#[cfg(target_os = "windows")]
+fn guid_to_string(guid: &GUID) -> String {
+    format!("{:?}", guid).to_lowercase()
+}
+
+/// Parse "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx" (braces optional). GUID::from panics on bad input.
+#[cfg(target_os = "windows")]
+fn parse_guid(value: &str) -> Option<GUID> {
+    let trimmed = value.trim().trim_start_matches('{').trim_end_matches('}');
+    let dashes_ok = trimmed.len() == 36
+        && [8, 13, 18, 23].iter().all(|&i| trimmed.as_bytes()[i] == b'-');
+    if !dashes_ok {
+        return None;
+    }
+    let hex: String = trimmed.chars().filter(|&c| c != '-').collect();
+    u128::from_str_radix(&hex, 16).ok().map(GUID::from_u128)
+}
+
+/// Helper to read a scheme's localized friendly name
+#[cfg(target_os = "windows")]
+fn read_power_scheme_name(scheme: &GUID) -> String {
     unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| format!("Failed to get default audio endpoint: {}", e))?;
-        
-        // Get audio session manager
-        let session_manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)
-            .map_err(|e| format!("Failed to get session manager: {}", e))?;
-        
-        // Get session enumerator
-        let session_enum: IAudioSessionEnumerator = session_manager.GetSessionEnumerator()
-            .map_err(|e| format!("Failed to get session enumerator: {}", e))?;
-        
-        let count = session_enum.GetCount()
-            .map_err(|e| format!("Failed to get session count: {}", e))?;
-        
-        let mut sessions = Vec::new();
-        
-        for i in 0..count {
-            let session: IAudioSessionControl = match session_enum.GetSession(i) {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            
-            // Get session control2 for more info
-            let session2: IAudioSessionControl2 = match session.cast() {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            
-            // Get process ID
-            let process_id = match session2.GetProcessId() {
-                Ok(pid) => pid,
-                Err(_) => continue,
-            };
-            
-            // Skip system sounds (process ID 0)
-            if process_id == 0 {
-                continue;
-            }
-            
-            // Get session state
-            let state = session.GetState().unwrap_or(AudioSessionState(0));
-            let is_active = state == AudioSessionState(1); // AudioSessionStateActive = 1
-            
-            // Get display name (or process name as fallback)
-            let display_name = session.GetDisplayName()
-                .map(|s| {
-                    let len = (0..).take_while(|&i| *s.0.add(i) != 0).count();
-                    let slice = std::slice::from_raw_parts(s.0, len);
-                    let name = String::from_utf16_lossy(slice);
-                    windows::Win32::System::Com::CoTaskMemFree(Some(s.0 as *const _));
-                    name
-                })
-                .unwrap_or_default();
-            
-            // Get app name from session identifier if display name is empty
-            let app_name = if display_name.is_empty() || display_name.starts_with("@{") {
-                // Try to get from session identifier
-                session2.GetSessionIdentifier()
-                    .map(|s| {
-                        let len = (0..).take_while(|&i| *s.0.add(i) != 0).count();
-                        let slice = std::slice::from_raw_parts(s.0, len);
-                        let id = String::from_utf16_lossy(slice);
-                        windows::Win32::System::Com::CoTaskMemFree(Some(s.0 as *const _));
-                        // Extract app name from session ID (usually contains exe path)
-                        id.split('\\')
-                            .last()
-                            .map(|n| n.split('|').next().unwrap_or(n))
-                            .map(|n| n.trim_end_matches(".exe").to_string())
-                            .unwrap_or_else(|| format!("App {}", process_id))
-                    })
-                    .unwrap_or_else(|_| format!("App {}", process_id))
-            } else {
-                display_name
-            };
-            
-            // Get volume interface
-            let volume: ISimpleAudioVolume = match session.cast() {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
-            
-            let level = volume.GetMasterVolume().unwrap_or(1.0);
-            let is_muted = volume.GetMute().map(|m| m.as_bool()).unwrap_or(false);
-            
-            sessions.push(AudioSession {
-                session_id: format!("{}", process_id),
-                app_name,
-                process_id,
-                volume: level,
-                is_muted,
-                is_active,
-            });
+        let mut size: u32 = 0;
+        let _ = PowerReadFriendlyName(HKEY::default(), Some(scheme), None, None, None, &mut size);
+        if size == 0 {
+            return guid_to_string(scheme);
+        }
+
+        let mut buffer = vec![0u8; size as usize];
+        let result = PowerReadFriendlyName(
+            HKEY::default(),
+            Some(scheme),
+            None,
+            None,
+            Some(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        if result.is_ok() {
+            reg_bytes_to_string(&buffer)
+        } else {
+            guid_to_string(scheme)
         }
-        
-        // Sort by active status (active first), then by name
-        sessions.sort_by(|a, b| {
-            match (a.is_active, b.is_active) {
-                (true, false) => std::cmp::Ordering::Less,
-                (false, true) => std::cmp::Ordering::Greater,
-                _ => a.app_name.to_lowercase().cmp(&b.app_name.to_lowercase()),
-            }
+    }
+}
+
+/// Helper to get the active power scheme GUID
+#[cfg(target_os = "windows")]
+fn get_active_power_scheme() -> Result<GUID, String> {
+    unsafe {
+        let mut active: *mut GUID = std::ptr::null_mut();
+        PowerGetActiveScheme(HKEY::default(), &mut active)
+            .ok()
+            .map_err(|e| format!("Failed to get active power scheme: {}", e))?;
+
+        // PowerGetActiveScheme allocates the GUID; we must LocalFree it
+        let guid = *active;
+        let _ = LocalFree(HLOCAL(active as *mut _));
+        Ok(guid)
+    }
+}
+
+/// List all power plans (classic schemes from PowerEnumerate)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn list_power_plans() -> Result<Vec<PowerPlan>, String> {
+    let active = get_active_power_scheme()?;
+    let mut plans = Vec::new();
+
+    for index in 0.. {
+        let mut guid = GUID::zeroed();
+        let mut size = std::mem::size_of::<GUID>() as u32;
+        let result = unsafe {
+            PowerEnumerate(
+                HKEY::default(),
+                None,
+                None,
+                ACCESS_SCHEME,
+                index,
+                Some(&mut guid as *mut GUID as *mut u8),
+                &mut size,
+            )
+        };
+        if result.is_err() {
+            break; // ERROR_NO_MORE_ITEMS
+        }
+
+        plans.push(PowerPlan {
+            guid: guid_to_string(&guid),
+            name: read_power_scheme_name(&guid),
+            is_active: guid == active,
         });
-        
-        Ok(sessions)
     }
+
+    Ok(plans)
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn list_audio_sessions() -> Result<Vec<AudioSession>, String> {
+fn list_power_plans() -> Result<Vec<PowerPlan>, String> {
     Ok(Vec::new())
 }
 
-/// Set volume for a specific audio session
+/// Get the currently active power plan
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn set_session_volume(process_id: u32, level: f32) -> Result<(), String> {
-    if level < 0.0 || level > 1.0 {
-        return Err("Volume level must be 0.0 to 1.0".to_string());
-    }
-    
-    unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| format!("Failed to get default audio endpoint: {}", e))?;
-        
-        let session_manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)
-            .map_err(|e| format!("Failed to get session manager: {}", e))?;
-        
-        let session_enum: IAudioSessionEnumerator = session_manager.GetSessionEnumerator()
-            .map_err(|e| format!("Failed to get session enumerator: {}", e))?;
-        
-        let count = session_enum.GetCount()
-            .map_err(|e| format!("Failed to get session count: {}", e))?;
-        
-        for i in 0..count {
-            let session: IAudioSessionControl = match session_enum.GetSession(i) {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            
-            let session2: IAudioSessionControl2 = match session.cast() {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            
-            let pid = match session2.GetProcessId() {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-            
-            if pid == process_id {
-                let volume: ISimpleAudioVolume = session.cast()
-                    .map_err(|e| format!("Failed to get volume interface: {}", e))?;
-                
-                volume.SetMasterVolume(level, std::ptr::null())
-                    .map_err(|e| format!("Failed to set volume: {}", e))?;
-                
-                return Ok(());
-            }
-        }
-        
-        Err(format!("Session not found for process ID {}", process_id))
-    }
+fn get_power_plan() -> Result<PowerPlan, String> {
+    let active = get_active_power_scheme()?;
+    Ok(PowerPlan {
+        guid: guid_to_string(&active),
+        name: read_power_scheme_name(&active),
+        is_active: true,
+    })
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn set_session_volume(_process_id: u32, _level: f32) -> Result<(), String> {
-    Err("Per-app volume not supported on this platform".to_string())
+fn get_power_plan() -> Result<PowerPlan, String> {
+    Err("Power plans not supported on this platform".to_string())
 }
 
-/// Mute/unmute a specific audio session
+/// Activate a power plan by GUID or by its friendly name (case-insensitive, e.g. "balanced")
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn set_session_mute(process_id: u32, muted: bool) -> Result<(), String> {
-    unsafe {
-        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-        
-        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
-            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
-        
-        let device = enumerator.GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| format!("Failed to get default audio endpoint: {}", e))?;
-        
-        let session_manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)
-            .map_err(|e| format!("Failed to get session manager: {}", e))?;
-        
-        let session_enum: IAudioSessionEnumerator = session_manager.GetSessionEnumerator()
-            .map_err(|e| format!("Failed to get session enumerator: {}", e))?;
-        
-        let count = session_enum.GetCount()
-            .map_err(|e| format!("Failed to get session count: {}", e))?;
-        
-        for i in 0..count {
-            let session: IAudioSessionControl = match session_enum.GetSession(i) {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            
-            let session2: IAudioSessionControl2 = match session.cast() {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            
-            let pid = match session2.GetProcessId() {
-                Ok(p) => p,
-                Err(_) => continue,
-            };
-            
-            if pid == process_id {
-                let volume: ISimpleAudioVolume = session.cast()
-                    .map_err(|e| format!("Failed to get volume interface: {}", e))?;
-                
-                volume.SetMute(muted, std::ptr::null())
-                    .map_err(|e| format!("Failed to set mute: {}", e))?;
-                
-                return Ok(());
+fn set_power_plan(guid_or_name: String) -> Result<(), String> {
+    let scheme = match parse_guid(&guid_or_name) {
+        Some(guid) => guid,
+        None => {
+            let plan = list_power_plans()?
+                .into_iter()
+                .find(|p| p.name.eq_ignore_ascii_case(guid_or_name.trim()))
+                .ok_or_else(|| format!("Power plan not found: {}", guid_or_name))?;
+            parse_guid(&plan.guid).ok_or_else(|| format!("Invalid power plan GUID: {}", plan.guid))?
+        }
+    };
+
+    unsafe { PowerSetActiveScheme(HKEY::default(), Some(&scheme)) }
+        .ok()
+        .map_err(|e| format!("Failed to set power plan: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_power_plan(_guid_or_name: String) -> Result<(), String> {
+    Err("Power plans not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "windows")]
+const POWER_PLAN_POLL_MS: u64 = 2000;
+
+/// Emit "power-plan-changed" (PowerPlan) when the active plan changes, whether from
+/// set_power_plan, Control Panel or powercfg
+#[cfg(target_os = "windows")]
+fn watch_power_plan_changes(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        use tauri::Emitter;
+
+        let Ok(mut last) = get_active_power_scheme() else { return };
+
+        loop {
+            thread::sleep(Duration::from_millis(POWER_PLAN_POLL_MS));
+
+            let Ok(active) = get_active_power_scheme() else { continue };
+            if active != last {
+                last = active;
+                let _ = app_handle.emit("power-plan-changed", PowerPlan {
+                    guid: guid_to_string(&active),
+                    name: read_power_scheme_name(&active),
+                    is_active: true,
+                });
             }
         }
-        
-        Err(format!("Session not found for process ID {}", process_id))
+    });
+}
+
+/// Get the Settings "Power mode" slider position.
+/// Returns "best_efficiency" | "balanced" | "better_performance" | "best_performance".
+/// On modern-standby laptops this is the only knob; classic plans are hidden there.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_power_mode() -> Result<String, String> {
+    let mut overlay = GUID::zeroed();
+    let status = unsafe { PowerGetActualOverlayScheme(&mut overlay) };
+    if status != 0 {
+        return Err(format!("Failed to get power mode (error {})", status));
     }
+
+    Ok(POWER_MODES
+        .iter()
+        .find(|(_, value)| GUID::from_u128(*value) == overlay)
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| guid_to_string(&overlay)))
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn set_session_mute(_process_id: u32, _muted: bool) -> Result<(), String> {
-    Err("Per-app mute not supported on this platform".to_string())
+fn get_power_mode() -> Result<String, String> {
+    Err("Power modes not supported on this platform".to_string())
 }
 
-// =============================================================================
-// Brightness Control Types
-// =============================================================================
+/// Move the "Power mode" slider (see get_power_mode for accepted values)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_power_mode(mode: String) -> Result<(), String> {
+    let overlay = POWER_MODES
+        .iter()
+        .find(|(name, _)| *name == mode)
+        .map(|(_, value)| GUID::from_u128(*value))
+        .ok_or_else(|| format!("Unknown power mode: {}", mode))?;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BrightnessInfo {
-    pub level: u32,       // 0-100
-    pub min: u32,         // minimum brightness level
-    pub max: u32,         // maximum brightness level
-    pub is_supported: bool,
+    let status = unsafe { PowerSetActiveOverlayScheme(&overlay) };
+    if status != 0 {
+        return Err(format!("Failed to set power mode (error {})", status));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_power_mode(_mode: String) -> Result<(), String> {
+    Err("Power modes not supported on this platform".to_string())
 }
 
 // =============================================================================
-// Brightness Control Commands
+// Battery Commands
 // =============================================================================
 
-/// Helper to get physical monitor handle
+/// Get battery status using Win32 GetSystemPowerStatus (no WinRT, no apartment init needed)
 #[cfg(target_os = "windows")]
-fn get_primary_physical_monitor() -> Result<PHYSICAL_MONITOR, String> {
+#[tauri::command]
+fn get_battery_info() -> Result<BatteryInfo, String> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
     unsafe {
-        // Get the primary monitor
-        let hwnd = GetForegroundWindow();
-        let hmonitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTOPRIMARY);
-        
-        // Get number of physical monitors
-        let mut num_monitors: u32 = 0;
-        GetNumberOfPhysicalMonitorsFromHMONITOR(hmonitor, &mut num_monitors)
-            .map_err(|e| format!("Failed to get monitor count: {}", e))?;
-        
-        if num_monitors == 0 {
-            return Err("No physical monitors found".to_string());
+        let mut sps = SYSTEM_POWER_STATUS::default();
+        GetSystemPowerStatus(&mut sps)
+            .map_err(|e| format!("Failed to get power status: {}", e))?;
+
+        // BatteryFlag bit 128 = no system battery present
+        let has_battery = (sps.BatteryFlag & 128) == 0;
+
+        // ACLineStatus: 0 = offline, 1 = online, 255 = unknown
+        let power_source = match sps.ACLineStatus {
+            0 => "battery",
+            1 => "ac",
+            _ => "unknown",
+        };
+
+        if !has_battery {
+            return Ok(BatteryInfo {
+                percent: 0,
+                is_charging: false,
+                is_battery_saver: false,
+                has_battery: false,
+                time_remaining_minutes: None,
+                power_source: power_source.to_string(),
+            });
         }
-        
-        // Get physical monitor handles
-        let mut monitors = vec![PHYSICAL_MONITOR::default(); num_monitors as usize];
-        GetPhysicalMonitorsFromHMONITOR(hmonitor, &mut monitors)
-            .map_err(|e| format!("Failed to get physical monitors: {}", e))?;
-        
-        Ok(monitors[0])
+
+        // BatteryLifePercent: 0–100, or 255 when unknown
+        let percent = if sps.BatteryLifePercent == 255 {
+            0
+        } else {
+            sps.BatteryLifePercent as u32
+        };
+
+        // BATTERY_FLAG_CHARGING (0x08) = battery is actively receiving charge.
+        // Do NOT use ACLineStatus == 1 ("cord connected") — laptops with battery
+        // conservation modes (e.g. ASUS capped at 80%) are plugged in but NOT charging,
+        // so ACLineStatus=1 even though no current is flowing into the battery.
+        let is_charging = (sps.BatteryFlag & 0x08) != 0;
+
+        // SystemStatusFlag bit 1 = battery saver on
+        let is_battery_saver = (sps.SystemStatusFlag & 1) != 0;
+
+        // BatteryLifeTime: seconds left on battery, u32::MAX when unknown or on AC
+        let time_remaining_minutes = if sps.BatteryLifeTime == u32::MAX {
+            None
+        } else {
+            Some(sps.BatteryLifeTime / 60)
+        };
+
+        Ok(BatteryInfo {
+            percent,
+            is_charging,
+            is_battery_saver,
+            has_battery: true,
+            time_remaining_minutes,
+            power_source: power_source.to_string(),
+        })
     }
 }
 
-/// Get system brightness: try WMI (laptops) first via brightness crate, then DDC/CI (external monitors)
-#[cfg(target_os = "windows")]
+#[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn get_system_brightness() -> Result<BrightnessInfo, String> {
-    // 1. Try brightness crate first (WMI - works on laptop internal panels)
-    for device_result in brightness::blocking::brightness_devices() {
-        if let Ok(device) = device_result {
-            if let Ok(level) = device.get() {
-                return Ok(BrightnessInfo {
-                    level: level.min(100),
-                    min: 0,
-                    max: 100,
-                    is_supported: true,
-                });
-            }
-        }
-    }
-
-    // 2. Fallback: DDC/CI for external monitors
-    unsafe {
-        let monitor = match get_primary_physical_monitor() {
-            Ok(m) => m,
-            Err(_) => {
-                return Ok(BrightnessInfo {
-                    level: 100,
-                    min: 0,
-                    max: 100,
-                    is_supported: false,
-                });
-            }
-        };
+fn get_battery_info() -> Result<BatteryInfo, String> {
+    Ok(BatteryInfo {
+        percent: 0,
+        is_charging: false,
+        is_battery_saver: false,
+        has_battery: false,
+        time_remaining_minutes: None,
+        power_source: "unknown".to_string(),
+    })
+}
 
-        let mut min_brightness: u32 = 0;
-        let mut current_brightness: u32 = 0;
-        let mut max_brightness: u32 = 0;
+/// Battery status for the battery widget; same as get_battery_info.
+/// `has_battery` is false (unsupported) on desktops without a battery.
+#[tauri::command]
+fn get_battery_status() -> Result<BatteryInfo, String> {
+    get_battery_info()
+}
 
-        let result = GetMonitorBrightness(
-            monitor.hPhysicalMonitor,
-            &mut min_brightness,
-            &mut current_brightness,
-            &mut max_brightness,
-        );
+#[cfg(target_os = "windows")]
+const BATTERY_POLL_MS: u64 = 2000;
 
-        let _ = DestroyPhysicalMonitor(monitor.hPhysicalMonitor);
+/// Percent levels that trigger "battery-low" while discharging, highest first
+static BATTERY_LOW_THRESHOLDS: Lazy<std::sync::Mutex<Vec<u32>>> =
+    Lazy::new(|| std::sync::Mutex::new(vec![20, 10]));
 
-        if result != 0 {
-            let range = max_brightness - min_brightness;
-            let normalized = if range > 0 {
-                ((current_brightness - min_brightness) * 100) / range
-            } else {
-                100
-            };
+/// A fired threshold re-arms once the level climbs this far back above it (or on charge),
+/// so a level hovering around the line doesn't fire repeatedly
+#[cfg(target_os = "windows")]
+const BATTERY_LOW_HYSTERESIS: u32 = 2;
 
-            Ok(BrightnessInfo {
-                level: normalized,
-                min: min_brightness,
-                max: max_brightness,
-                is_supported: true,
-            })
-        } else {
-            Ok(BrightnessInfo {
-                level: 100,
-                min: 0,
-                max: 100,
-                is_supported: false,
-            })
-        }
+/// Set the low-battery warning levels (1-99; duplicates are dropped)
+#[tauri::command]
+fn set_battery_low_thresholds(thresholds: Vec<u32>) -> Result<(), String> {
+    if let Some(bad) = thresholds.iter().find(|t| **t == 0 || **t >= 100) {
+        return Err(format!("Invalid battery threshold: {}", bad));
     }
+    let mut thresholds = thresholds;
+    thresholds.sort_unstable_by(|a, b| b.cmp(a));
+    thresholds.dedup();
+    *BATTERY_LOW_THRESHOLDS.lock().unwrap_or_else(|e| e.into_inner()) = thresholds;
+    Ok(())
+}
+
+/// Get the low-battery warning levels
+#[tauri::command]
+fn get_battery_low_thresholds() -> Vec<u32> {
+    BATTERY_LOW_THRESHOLDS.lock().unwrap_or_else(|e| e.into_inner()).clone()
 }
 
-#[cfg(not(target_os = "windows"))]
-#[tauri::command]
-fn get_system_brightness() -> Result<BrightnessInfo, String> {
-    Ok(BrightnessInfo {
-        level: 100,
-        min: 0,
-        max: 100,
-        is_supported: false,
-    })
+/// Return the lowest newly crossed threshold, updating which thresholds have fired
+#[cfg(target_os = "windows")]
+fn check_battery_low(info: &BatteryInfo, fired: &mut std::collections::HashSet<u32>) -> Option<u32> {
+    let thresholds = BATTERY_LOW_THRESHOLDS.lock().unwrap_or_else(|e| e.into_inner());
+    let discharging = !info.is_charging && info.power_source != "ac";
+
+    fired.retain(|t| thresholds.contains(t) && discharging && info.percent < t + BATTERY_LOW_HYSTERESIS);
+    if !discharging {
+        return None;
+    }
+
+    let crossed: Vec<u32> = thresholds
+        .iter()
+        .copied()
+        .filter(|t| info.percent <= *t && !fired.contains(t))
+        .collect();
+    fired.extend(crossed.iter().copied());
+    crossed.into_iter().min()
 }
 
-/// Set system brightness (0-100): try WMI (laptops) first, then DDC/CI (external monitors)
+/// Emit "battery-changed" (BatteryInfo) on plug/unplug, percent or estimate changes, and
+/// "battery-low" (percent) once per threshold crossing while discharging.
+/// GetSystemPowerStatus is cheap, so this just polls it and emits on change.
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn set_system_brightness(level: u32) -> Result<(), String> {
-    let level = level.min(100);
+fn watch_battery_changes(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        use tauri::Emitter;
 
-    // 1. Try brightness crate first (WMI - works on laptop internal panels)
-    for device_result in brightness::blocking::brightness_devices() {
-        if let Ok(device) = device_result {
-            if device.set(level).is_ok() {
-                return Ok(());
-            }
+        let mut last = match get_battery_info() {
+            Ok(info) if info.has_battery => info,
+            _ => return, // Desktops: nothing to watch
+        };
+        let mut fired = std::collections::HashSet::new();
+        if check_battery_low(&last, &mut fired).is_some() {
+            let _ = app_handle.emit("battery-low", last.percent);
         }
-    }
 
-    // 2. Fallback: DDC/CI for external monitors
-    unsafe {
-        let monitor = get_primary_physical_monitor()?;
+        loop {
+            thread::sleep(Duration::from_millis(BATTERY_POLL_MS));
 
-        let mut min_brightness: u32 = 0;
-        let mut current_brightness: u32 = 0;
-        let mut max_brightness: u32 = 0;
+            let Ok(info) = get_battery_info() else { continue };
+            if check_battery_low(&info, &mut fired).is_some() {
+                let _ = app_handle.emit("battery-low", info.percent);
+            }
+            if info != last {
+                last = info.clone();
+                let _ = app_handle.emit("battery-changed", info);
+            }
+        }
+    });
+}
 
-        let _ = GetMonitorBrightness(
-            monitor.hPhysicalMonitor,
-            &mut min_brightness,
-            &mut current_brightness,
-            &mut max_brightness,
-        );
+// =============================================================================
+// Battery Saver Commands
+// =============================================================================
 
-        let range = max_brightness - min_brightness;
-        let actual_level = min_brightness + (level * range) / 100;
+/// Helper to open a URI or path with the shell (ms-settings: pages, folders, ...)
+#[cfg(target_os = "windows")]
+fn shell_open(target: &str) -> Result<(), String> {
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            &HSTRING::from("open"),
+            &HSTRING::from(target),
+            None,
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    if result.0 as isize <= 32 {
+        return Err(format!(
+            "Failed to open {} (ShellExecute returned {})",
+            target,
+            result.0 as isize
+        ));
+    }
+    Ok(())
+}
 
-        let result = SetMonitorBrightness(monitor.hPhysicalMonitor, actual_level);
+/// Get whether battery saver (energy saver) is currently on
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_battery_saver() -> Result<bool, String> {
+    Ok(get_battery_info()?.is_battery_saver)
+}
 
-        let _ = DestroyPhysicalMonitor(monitor.hPhysicalMonitor);
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_battery_saver() -> Result<bool, String> {
+    Ok(false)
+}
 
-        if result != 0 {
-            Ok(())
-        } else {
-            Err("Failed to set brightness - DDC/CI may not be supported".to_string())
-        }
+/// Turn battery saver on or off.
+/// Windows has no public API to flip battery saver, so when the state needs to change we open
+/// the battery saver Settings page instead. Returns true if the requested state is in effect,
+/// false if the user has to finish the change in Settings.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_battery_saver(enabled: bool) -> Result<bool, String> {
+    let battery = get_battery_info()?;
+    if !battery.has_battery {
+        return Err("Battery saver requires a battery".to_string());
     }
+    if battery.is_battery_saver == enabled {
+        return Ok(true);
+    }
+
+    shell_open("ms-settings:batterysaver")?;
+    Ok(false)
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn set_system_brightness(_level: u32) -> Result<(), String> {
-    Err("Brightness control not supported on this platform".to_string())
+fn set_battery_saver(_enabled: bool) -> Result<bool, String> {
+    Err("Battery saver not supported on this platform".to_string())
 }
 
 // =============================================================================
-// Notification Commands
+// System Stats Commands
 // =============================================================================
 
-/// Helper to poll notification listener access.
-/// Updates the global cache on success.
+/// GetSystemTimes sample (idle, kernel, user) in 100ns ticks
 #[cfg(target_os = "windows")]
-fn poll_notification_access() -> Result<UserNotificationListenerAccessStatus, String> {
-    let listener = UserNotificationListener::Current()
-        .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+type CpuTimes = (u64, u64, u64);
 
-    let op = listener.RequestAccessAsync()
-        .map_err(|e| format!("Failed to request notification access: {}", e))?;
+/// The get_system_stats command's previous sample. The watcher keeps its own, so a poll from
+/// the frontend never shortens (or steals) the watcher's delta.
+#[cfg(target_os = "windows")]
+static CPU_TIMES_SAMPLE: Lazy<std::sync::Mutex<Option<CpuTimes>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
 
-    for _ in 0..POLL_MAX_ITERS {
-        let status = op.Status().map_err(|e| format!("Failed to get status: {}", e))?;
-        if status == AsyncStatus::Completed {
-            let result = op.GetResults().map_err(|e| format!("Failed to get results: {}", e))?;
-            NOTIFICATION_ACCESS_GRANTED.store(
-                result == UserNotificationListenerAccessStatus::Allowed,
-                Ordering::Relaxed,
-            );
-            return Ok(result);
-        }
-        if status == AsyncStatus::Error {
-            return Err("Async operation failed".to_string());
-        }
-        thread::sleep(Duration::from_millis(POLL_SLEEP_MS));
-    }
-    Err("Timeout waiting for notification access".to_string())
-}
+#[cfg(target_os = "windows")]
+const SYSTEM_STATS_POLL_MS: u64 = 2000;
 
-#[cfg(not(target_os = "windows"))]
-fn poll_notification_access() -> Result<(), String> {
-    Err("Notifications not supported on this platform".to_string())
+#[cfg(target_os = "windows")]
+fn filetime_ticks(ft: &windows::Win32::Foundation::FILETIME) -> u64 {
+    ((ft.dwHighDateTime as u64) << 32) | ft.dwLowDateTime as u64
 }
 
-/// Helper to poll notifications list
+/// CPU usage (busy share of the ticks) since `sample`, which is replaced with the current one.
+/// 0 when there's no previous sample yet; that call only takes the baseline.
 #[cfg(target_os = "windows")]
-fn poll_notifications_list(listener: &UserNotificationListener) -> Result<Vec<UserNotification>, String> {
-    let op = listener.GetNotificationsAsync(windows::UI::Notifications::NotificationKinds::Toast)
-        .map_err(|e| format!("Failed to get notifications: {}", e))?;
+fn sample_cpu_percent(sample: &mut Option<CpuTimes>) -> Result<f32, String> {
+    use windows::Win32::Foundation::FILETIME;
+    use windows::Win32::System::Threading::GetSystemTimes;
 
-    for _ in 0..POLL_MAX_ITERS {
-        let status = op.Status().map_err(|e| format!("Failed to get status: {}", e))?;
-        if status == AsyncStatus::Completed {
-            let notifs = op.GetResults()
-                .map_err(|e| format!("Failed to get results: {}", e))?;
+    let (mut idle, mut kernel, mut user) = (FILETIME::default(), FILETIME::default(), FILETIME::default());
+    unsafe { GetSystemTimes(Some(&mut idle), Some(&mut kernel), Some(&mut user)) }
+        .map_err(|e| format!("Failed to get system times: {}", e))?;
+    let now = (filetime_ticks(&idle), filetime_ticks(&kernel), filetime_ticks(&user));
 
-            let mut result = Vec::new();
-            let count = notifs.Size().unwrap_or(0);
-            for i in 0..count {
-                if let Ok(n) = notifs.GetAt(i) {
-                    result.push(n);
-                }
-            }
-            return Ok(result);
-        }
-        if status == AsyncStatus::Error {
-            return Err("Async operation failed".to_string());
-        }
-        thread::sleep(Duration::from_millis(POLL_SLEEP_MS));
+    let Some(prev) = sample.replace(now) else {
+        return Ok(0.0);
+    };
+
+    // Kernel time includes idle time
+    let idle_delta = now.0.saturating_sub(prev.0);
+    let total_delta = now.1.saturating_sub(prev.1) + now.2.saturating_sub(prev.2);
+    if total_delta == 0 {
+        return Ok(0.0);
     }
-    Err("Timeout waiting for notifications".to_string())
+    let busy = total_delta.saturating_sub(idle_delta) as f32 / total_delta as f32;
+    Ok((busy * 100.0).clamp(0.0, 100.0))
 }
 
-#[cfg(not(target_os = "windows"))]
-fn poll_notifications_list(_listener: &()) -> Result<Vec<()>, String> {
-    Err("Notifications not supported on this platform".to_string())
+/// CPU usage since `cpu_sample` and physical memory usage (GlobalMemoryStatusEx)
+#[cfg(target_os = "windows")]
+fn read_system_stats(cpu_sample: &mut Option<CpuTimes>) -> Result<SystemStats, String> {
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+    let cpu_percent = sample_cpu_percent(cpu_sample)?;
+
+    let mut mem = MEMORYSTATUSEX {
+        dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+        ..Default::default()
+    };
+    unsafe { GlobalMemoryStatusEx(&mut mem) }
+        .map_err(|e| format!("Failed to get memory status: {}", e))?;
+
+    const MB: u64 = 1024 * 1024;
+    Ok(SystemStats {
+        cpu_percent,
+        mem_used_mb: mem.ullTotalPhys.saturating_sub(mem.ullAvailPhys) / MB,
+        mem_total_mb: mem.ullTotalPhys / MB,
+    })
 }
 
-/// Subscribe to Windows NotificationChanged with retry for transient startup races.
-/// Some systems return HRESULT 0x80070490 (Element not found) even when polling works.
+/// Get CPU usage (GetSystemTimes deltas) and physical memory usage (GlobalMemoryStatusEx)
 #[cfg(target_os = "windows")]
-fn subscribe_notification_changed(
-    listener: &UserNotificationListener,
-    app_handle: &tauri::AppHandle,
-) -> bool {
-    const RETRIES: usize = 3;
-    const RETRY_DELAY_MS: u64 = 500;
-    const E_ELEMENT_NOT_FOUND: i32 = 0x80070490u32 as i32;
+#[tauri::command]
+fn get_system_stats() -> Result<SystemStats, String> {
+    let mut sample = CPU_TIMES_SAMPLE.lock().unwrap_or_else(|e| e.into_inner());
+    read_system_stats(&mut sample)
+}
 
-    for attempt in 1..=RETRIES {
-        let handle_for_event = app_handle.clone();
-        let handler = TypedEventHandler::new(
-            move |_listener: &Option<UserNotificationListener>,
-                  _args: &Option<UserNotificationChangedEventArgs>| {
-                use tauri::Emitter;
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_system_stats() -> Result<SystemStats, String> {
+    Err("System stats not supported on this platform".to_string())
+}
 
-                // Try to intercept new notifications: read content, dismiss from Windows, emit to frontend
-                if let Some(args) = _args {
-                    if let Ok(UserNotificationChangedKind::Added) = args.ChangeKind() {
-                        if let Ok(notif_id) = args.UserNotificationId() {
-                            if let Ok(listener) = UserNotificationListener::Current() {
-                                if let Ok(notifications) = poll_notifications_list(&listener) {
-                                    if let Some(notif) = notifications.iter().find(|n| n.Id().unwrap_or(0) == notif_id) {
-                                        if let Some(sn) = extract_notification(notif, 0) {
-                                            let _ = handle_for_event.emit("notification-added", &sn);
-                                            // Dismiss from Windows to suppress native toast banner
-                                            let _ = listener.RemoveNotification(notif_id);
-                                            return Ok(());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+/// Emit "system-stats" (SystemStats) every couple of seconds for the performance widget.
+/// Skips the emit when nothing moved so an idle machine doesn't wake the webview.
+#[cfg(target_os = "windows")]
+fn watch_system_stats(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        use tauri::Emitter;
 
-                // Fallback: emit generic change event (removed notifications, or failed to read)
-                let _ = handle_for_event.emit("notification-changed", ());
-                Ok(())
-            },
-        );
+        let mut cpu_sample = None;
+        let _ = sample_cpu_percent(&mut cpu_sample); // Baseline for the first delta
+        let mut last: Option<SystemStats> = None;
 
-        match listener.NotificationChanged(&handler) {
-            Ok(_) => {
-                if attempt > 1 {
-                    eprintln!(
-                        "[PILLAR] Subscribed to NotificationChanged after retry {}",
-                        attempt
-                    );
-                } else {
-                    eprintln!("[PILLAR] Successfully subscribed to NotificationChanged");
-                }
-                return true;
+        loop {
+            thread::sleep(Duration::from_millis(SYSTEM_STATS_POLL_MS));
+
+            let Ok(stats) = read_system_stats(&mut cpu_sample) else { continue };
+            let unchanged = last.as_ref().is_some_and(|prev| {
+                (prev.cpu_percent - stats.cpu_percent).abs() < 1.0 && prev.mem_used_mb == stats.mem_used_mb
+            });
+            if unchanged {
+                continue;
             }
-            Err(e) => {
-                let code = e.code().0;
-                let is_not_found = code == E_ELEMENT_NOT_FOUND;
+            last = Some(stats.clone());
+            let _ = app_handle.emit("system-stats", stats);
+        }
+    });
+}
 
-                // Retry only for the common startup race case.
-                if is_not_found && attempt < RETRIES {
-                    thread::sleep(Duration::from_millis(RETRY_DELAY_MS));
+// =============================================================================
+// Network Commands
+// =============================================================================
+
+/// SSID and signal quality (0-100) of the first connected Wi-Fi interface, None when no WLAN is
+/// connected (or no WLAN service)
+#[cfg(target_os = "windows")]
+fn connected_wifi() -> Option<(String, u32)> {
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::NetworkManagement::WiFi::{
+        wlan_interface_state_connected, wlan_intf_opcode_current_connection, WlanCloseHandle, WlanEnumInterfaces,
+        WlanFreeMemory, WlanOpenHandle, WlanQueryInterface, WLAN_CONNECTION_ATTRIBUTES, WLAN_INTERFACE_INFO_LIST,
+    };
+
+    unsafe {
+        let mut version = 0u32;
+        let mut client = HANDLE::default();
+        // Client version 2 = Vista and later
+        if WlanOpenHandle(2, None, &mut version, &mut client) != 0 {
+            return None;
+        }
+
+        let mut connection = None;
+        let mut list: *mut WLAN_INTERFACE_INFO_LIST = std::ptr::null_mut();
+        if WlanEnumInterfaces(client, None, &mut list) == 0 && !list.is_null() {
+            let count = (*list).dwNumberOfItems as usize;
+            let interfaces = std::slice::from_raw_parts((*list).InterfaceInfo.as_ptr(), count);
+
+            for interface in interfaces.iter().filter(|i| i.isState == wlan_interface_state_connected) {
+                let mut size = 0u32;
+                let mut data: *mut std::ffi::c_void = std::ptr::null_mut();
+                let status = WlanQueryInterface(
+                    client,
+                    &interface.InterfaceGuid,
+                    wlan_intf_opcode_current_connection,
+                    None,
+                    &mut size,
+                    &mut data,
+                    None,
+                );
+                if status != 0 || data.is_null() {
                     continue;
                 }
 
-                if is_not_found {
-                    eprintln!(
-                        "[PILLAR] NotificationChanged not available on this system; using polling fallback"
-                    );
-                } else {
-                    eprintln!("[PILLAR] Failed to subscribe to NotificationChanged: {:?}", e);
-                    eprintln!("[PILLAR] Notifications will still work via polling fallback");
+                let attrs = &(*(data as *const WLAN_CONNECTION_ATTRIBUTES)).wlanAssociationAttributes;
+                let raw = &attrs.dot11Ssid;
+                let len = (raw.uSSIDLength as usize).min(raw.ucSSID.len());
+                let name = String::from_utf8_lossy(&raw.ucSSID[..len]).into_owned();
+                let quality = attrs.wlanSignalQuality.min(100);
+                WlanFreeMemory(data);
+
+                if !name.is_empty() {
+                    connection = Some((name, quality));
+                    break;
                 }
-                return false;
             }
+            WlanFreeMemory(list as *const std::ffi::c_void);
         }
+
+        WlanCloseHandle(client, None);
+        connection
     }
+}
 
-    false
+/// Read connectivity from the Network List Manager and the Wi-Fi SSID from the WLAN service.
+/// Call with COM initialized.
+#[cfg(target_os = "windows")]
+fn read_network_status() -> Result<NetworkStatus, String> {
+    use windows::Win32::Networking::NetworkListManager::{
+        INetworkListManager, NetworkListManager, NLM_CONNECTIVITY_DISCONNECTED, NLM_CONNECTIVITY_IPV4_INTERNET,
+        NLM_CONNECTIVITY_IPV6_INTERNET,
+    };
+
+    let connectivity = unsafe {
+        let nlm: INetworkListManager = CoCreateInstance(&NetworkListManager, None, CLSCTX_ALL)
+            .map_err(|e| format!("Failed to create network list manager: {}", e))?;
+        nlm.GetConnectivity()
+            .map_err(|e| format!("Failed to get connectivity: {}", e))?
+    };
+
+    let internet = NLM_CONNECTIVITY_IPV4_INTERNET.0 | NLM_CONNECTIVITY_IPV6_INTERNET.0;
+    let is_connected = connectivity.0 & internet != 0;
+
+    let (ssid, signal_quality) = connected_wifi().unzip();
+    let connection_type = if ssid.is_some() {
+        "wifi"
+    } else if connectivity != NLM_CONNECTIVITY_DISCONNECTED {
+        "ethernet"
+    } else {
+        "none"
+    };
+
+    Ok(NetworkStatus {
+        is_connected,
+        connection_type: connection_type.to_string(),
+        ssid,
+        signal_quality,
+    })
 }
 
-/// Request notification access and check if granted.
-/// Also updates the cached access flag used by get_notifications().
+/// Get whether we're online, and over Wi-Fi or a wired connection
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn check_notification_access() -> Result<bool, String> {
-    let status = poll_notification_access()?;
-    let allowed = status == UserNotificationListenerAccessStatus::Allowed;
-    NOTIFICATION_ACCESS_GRANTED.store(allowed, Ordering::Relaxed);
-    Ok(allowed)
+async fn get_network_status() -> Result<NetworkStatus, String> {
+    spawn_com_blocking(read_network_status).await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+async fn get_network_status() -> Result<NetworkStatus, String> {
+    Err("Network status not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Network Events
+// =============================================================================
+
+/// Signal quality isn't covered by NLM events, so it's polled
+#[cfg(target_os = "windows")]
+const NETWORK_POLL_MS: u64 = 5000;
+
+/// Last status sent as "network-changed", shared by the NLM sink and the signal poller
+#[cfg(target_os = "windows")]
+static LAST_NETWORK_STATUS: Lazy<std::sync::Mutex<Option<NetworkStatus>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
+/// Re-read the network status and emit "network-changed" (NetworkStatus) if it differs from the
+/// last one sent. Call with COM initialized.
+#[cfg(target_os = "windows")]
+fn emit_network_status_if_changed(app_handle: &tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let status = match read_network_status() {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("[PILLAR] {}", e);
+            return;
+        }
+    };
+
+    let mut last = LAST_NETWORK_STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    if last.as_ref() != Some(&status) {
+        *last = Some(status.clone());
+        let _ = app_handle.emit("network-changed", status);
+    }
+}
+
+/// Poll for Wi-Fi signal quality changes (connectivity changes also arrive via the NLM sink)
+#[cfg(target_os = "windows")]
+fn watch_network_changes(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        let _com = ComGuard::new();
+
+        loop {
+            emit_network_status_if_changed(&app_handle);
+            thread::sleep(Duration::from_millis(NETWORK_POLL_MS));
+        }
+    });
+}
+
+/// INetworkListManagerEvents sink. On connectivity changes, re-reads the full status and emits
+/// "network-changed" (NetworkStatus).
+#[cfg(target_os = "windows")]
+#[windows::core::implement(windows::Win32::Networking::NetworkListManager::INetworkListManagerEvents)]
+struct NetworkEventsNotifier {
+    app_handle: tauri::AppHandle,
 }
 
-#[cfg(not(target_os = "windows"))]
-#[tauri::command]
-fn check_notification_access() -> Result<bool, String> {
-    Ok(false)
+// Callbacks run on a system thread and must not block; the WLAN query happens on a worker
+#[cfg(target_os = "windows")]
+impl windows::Win32::Networking::NetworkListManager::INetworkListManagerEvents_Impl for NetworkEventsNotifier_Impl {
+    fn ConnectivityChanged(
+        &self,
+        _new_connectivity: windows::Win32::Networking::NetworkListManager::NLM_CONNECTIVITY,
+    ) -> windows::core::Result<()> {
+        let app_handle = self.app_handle.clone();
+        thread::spawn(move || {
+            let _com = ComGuard::new();
+            emit_network_status_if_changed(&app_handle);
+        });
+        Ok(())
+    }
 }
 
-/// Extract a SystemNotification from a Windows UserNotification.
-/// Returns None if the notification has no meaningful content.
+/// Advised connection point, kept for the app lifetime (dropping it ends the callbacks)
 #[cfg(target_os = "windows")]
-fn extract_notification(notif: &UserNotification, idx: usize) -> Option<SystemNotification> {
-    let id = notif.Id().unwrap_or(idx as u32);
+struct RegisteredNetworkSink {
+    _manager: windows::Win32::Networking::NetworkListManager::INetworkListManager,
+    _point: windows::Win32::System::Com::IConnectionPoint,
+    _sink: windows::Win32::Networking::NetworkListManager::INetworkListManagerEvents,
+}
 
-    let app_name = notif
-        .AppInfo()
-        .ok()
-        .and_then(|app_info| app_info.DisplayInfo().ok())
-        .and_then(|display_info| display_info.DisplayName().ok())
-        .map(|h| h.to_string())
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| "Windows App".to_string());
+#[cfg(target_os = "windows")]
+unsafe impl Send for RegisteredNetworkSink {}
+#[cfg(target_os = "windows")]
+unsafe impl Sync for RegisteredNetworkSink {}
 
-    let aumid = notif
-        .AppInfo()
-        .ok()
-        .and_then(|app_info| app_info.AppUserModelId().ok())
-        .map(|h| h.to_string())
-        .filter(|s| !s.is_empty());
+#[cfg(target_os = "windows")]
+static NETWORK_EVENTS_SINK: once_cell::sync::OnceCell<RegisteredNetworkSink> = once_cell::sync::OnceCell::new();
 
-    let notification = notif.Notification().ok()?;
-    let visual = notification.Visual().ok()?;
+/// Advise a Network List Manager events sink. Call from an MTA thread.
+#[cfg(target_os = "windows")]
+fn subscribe_network_changed(app_handle: &tauri::AppHandle) -> bool {
+    use windows::core::Interface;
+    use windows::Win32::Networking::NetworkListManager::{
+        INetworkListManager, INetworkListManagerEvents, NetworkListManager,
+    };
+    use windows::Win32::System::Com::IConnectionPointContainer;
 
-    let mut title = String::new();
-    let mut body = String::new();
+    if NETWORK_EVENTS_SINK.get().is_some() {
+        return true;
+    }
 
-    if let Ok(bindings) = visual.Bindings() {
-        if let Ok(count) = bindings.Size() {
-            for i in 0..count {
-                if let Ok(binding) = bindings.GetAt(i) {
-                    if let Ok(elements) = binding.GetTextElements() {
-                        if let Ok(elem_count) = elements.Size() {
-                            for j in 0..elem_count {
-                                if let Ok(elem) = elements.GetAt(j) {
-                                    if let Ok(text) = elem.Text() {
-                                        let text_str = text.to_string();
-                                        if title.is_empty() {
-                                            title = text_str;
-                                        } else if body.is_empty() {
-                                            body = text_str;
-                                        } else {
-                                            body.push('\n');
-                                            body.push_str(&text_str);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    break; // Only process first binding
-                }
+    let result = unsafe {
+        CoCreateInstance::<_, INetworkListManager>(&NetworkListManager, None, CLSCTX_ALL).and_then(|manager| {
+            let container: IConnectionPointContainer = manager.cast()?;
+            let point = container.FindConnectionPoint(&INetworkListManagerEvents::IID)?;
+            let sink: INetworkListManagerEvents = NetworkEventsNotifier {
+                app_handle: app_handle.clone(),
             }
+            .into();
+            point.Advise(&sink)?;
+            Ok(RegisteredNetworkSink { _manager: manager, _point: point, _sink: sink })
+        })
+    };
+
+    match result {
+        Ok(registered) => {
+            let _ = NETWORK_EVENTS_SINK.set(registered);
+            eprintln!("[PILLAR] Successfully subscribed to network changes");
+            true
+        }
+        Err(e) => {
+            eprintln!("[PILLAR] Failed to subscribe to network changes: {:?}", e);
+            false
         }
     }
+}
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
+// =============================================================================
+// Bluetooth Commands
+// =============================================================================
 
-    let timestamp = notif
-        .CreationTime()
+/// Device enumeration and GATT reads go over the radio; allow ~2s instead of POLL_MAX_ITERS
+#[cfg(target_os = "windows")]
+const BLUETOOTH_POLL_MAX_ITERS: usize = 400;
+
+#[cfg(target_os = "windows")]
+const AEP_IS_CONNECTED: &str = "System.Devices.Aep.IsConnected";
+#[cfg(target_os = "windows")]
+const AEP_CONTAINER_ID: &str = "System.Devices.Aep.ContainerId";
+/// DEVPKEY_Bluetooth_Battery, set on the device node by the hands-free/AVRCP profile drivers.
+/// This is what Settings shows for classic headsets.
+#[cfg(target_os = "windows")]
+const DEVPKEY_BLUETOOTH_BATTERY: &str = "{104EA319-6EE2-4701-BD47-8DDBF425BBE5} 2";
+
+/// Helper to read a boxed value (IReference<T>) out of a DeviceInformation property map
+#[cfg(target_os = "windows")]
+fn device_property<T: windows::core::RuntimeType + 'static>(
+    properties: &windows::Foundation::Collections::IMapView<HSTRING, windows::core::IInspectable>,
+    key: &str,
+) -> Option<T> {
+    properties
+        .Lookup(&HSTRING::from(key))
+        .ok()?
+        .cast::<windows::Foundation::IReference<T>>()
+        .ok()?
+        .Value()
         .ok()
-        .map(|dt| {
-            let ticks: i64 = dt.UniversalTime;
-            const EPOCH_OFFSET_100NS: i64 = 11644473600 * 10_000_000;
-            let unix_ms = ((ticks - EPOCH_OFFSET_100NS) / 10_000) as u64;
-            unix_ms
-        })
-        .filter(|&t| t > 0 && t < now + 86400_000)
-        .unwrap_or_else(|| now.saturating_sub(idx as u64 * 60000));
+}
 
-    if title.is_empty() && body.is_empty() {
+#[cfg(target_os = "windows")]
+fn property_list(keys: &[&str]) -> Result<windows::Foundation::Collections::IIterable<HSTRING>, String> {
+    windows::Foundation::Collections::IIterable::<HSTRING>::try_from(
+        keys.iter().map(|k| HSTRING::from(*k)).collect::<Vec<_>>(),
+    )
+    .map_err(|e| format!("Failed to build property list: {}", e))
+}
+
+/// Paired devices matching a BluetoothDevice/BluetoothLEDevice selector, with connection state
+#[cfg(target_os = "windows")]
+fn find_paired_bluetooth(
+    selector: &HSTRING,
+) -> Result<Vec<windows::Devices::Enumeration::DeviceInformation>, String> {
+    use windows::Devices::Enumeration::DeviceInformation;
+
+    let properties = property_list(&[AEP_IS_CONNECTED, AEP_CONTAINER_ID])?;
+    let op = DeviceInformation::FindAllAsyncAqsFilterAndAdditionalProperties(selector, &properties)
+        .map_err(|e| format!("Failed to enumerate Bluetooth devices: {}", e))?;
+    let devices = poll_async_op_with_limit(op, "Bluetooth devices", BLUETOOTH_POLL_MAX_ITERS)?;
+    Ok(devices.into_iter().collect())
+}
+
+/// Battery level from the GATT Battery Service. Fails for devices that protect it (e.g. HID
+/// keyboards and mice, whose battery service Windows keeps for itself).
+#[cfg(target_os = "windows")]
+fn gatt_battery_level(device_id: &HSTRING) -> Option<u32> {
+    use windows::Devices::Bluetooth::GenericAttributeProfile::{
+        GattCharacteristicUuids, GattCommunicationStatus, GattServiceUuids,
+    };
+    use windows::Devices::Bluetooth::{BluetoothCacheMode, BluetoothLEDevice};
+
+    fn poll<T: windows::core::RuntimeType + 'static>(
+        op: windows::core::Result<windows::Foundation::IAsyncOperation<T>>,
+    ) -> Option<T> {
+        poll_async_op_with_limit(op.ok()?, "GATT operation", BLUETOOTH_POLL_MAX_ITERS).ok()
+    }
+
+    let device = poll(BluetoothLEDevice::FromIdAsync(device_id))?;
+    let services = poll(
+        device.GetGattServicesForUuidWithCacheModeAsync(GattServiceUuids::Battery().ok()?, BluetoothCacheMode::Cached),
+    )?;
+    if services.Status().ok()? != GattCommunicationStatus::Success {
+        let _ = device.Close();
         return None;
     }
+    let service = services.Services().ok()?.GetAt(0).ok()?;
+
+    let level = (|| {
+        let characteristics = poll(service.GetCharacteristicsForUuidWithCacheModeAsync(
+            GattCharacteristicUuids::BatteryLevel().ok()?,
+            BluetoothCacheMode::Cached,
+        ))?;
+        if characteristics.Status().ok()? != GattCommunicationStatus::Success {
+            return None;
+        }
+        let characteristic = characteristics.Characteristics().ok()?.GetAt(0).ok()?;
+        let read = poll(characteristic.ReadValueWithCacheModeAsync(BluetoothCacheMode::Uncached))?;
+        if read.Status().ok()? != GattCommunicationStatus::Success {
+            return None;
+        }
+        let reader = DataReader::FromBuffer(&read.Value().ok()?).ok()?;
+        reader.ReadByte().ok().map(|b| (b as u32).min(100))
+    })();
+
+    // Open services hold a connection to the device; release them right away
+    let _ = service.Close();
+    let _ = device.Close();
+    level
+}
 
-    Some(SystemNotification {
-        id,
-        app_name,
-        title,
-        body,
-        timestamp,
-        aumid,
+/// Battery level the profile drivers published on any device node of the device's container
+#[cfg(target_os = "windows")]
+fn device_node_battery_level(container_id: windows::core::GUID) -> Option<u32> {
+    use windows::Devices::Enumeration::{DeviceInformation, DeviceInformationKind};
+
+    let filter = HSTRING::from(format!("System.Devices.ContainerId:=\"{{{:?}}}\"", container_id));
+    let properties = property_list(&[DEVPKEY_BLUETOOTH_BATTERY]).ok()?;
+    let op = DeviceInformation::FindAllAsyncWithKindAqsFilterAndAdditionalProperties(
+        &filter,
+        &properties,
+        DeviceInformationKind::Device,
+    )
+    .ok()?;
+    let nodes = poll_async_op_with_limit(op, "device nodes", BLUETOOTH_POLL_MAX_ITERS).ok()?;
+
+    nodes.into_iter().find_map(|node| {
+        let properties = node.Properties().ok()?;
+        device_property::<u8>(&properties, DEVPKEY_BLUETOOTH_BATTERY).map(|b| (b as u32).min(100))
     })
 }
 
-/// Get recent notifications.
-/// Uses cached access status to avoid re-polling access on every call.
+/// Paired classic and LE devices, connected first. Dual-mode devices appear under both
+/// selectors, so entries are merged per device container.
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn get_notifications() -> Result<Vec<SystemNotification>, String> {
-    if !NOTIFICATION_ACCESS_GRANTED.load(Ordering::Relaxed) {
-        return Ok(Vec::new());
-    }
+fn read_bluetooth_devices() -> Result<Vec<BluetoothDeviceInfo>, String> {
+    use windows::Devices::Bluetooth::{BluetoothDevice, BluetoothLEDevice};
 
-    let listener = UserNotificationListener::Current()
-        .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+    let selectors = [
+        (BluetoothDevice::GetDeviceSelectorFromPairingState(true), false),
+        (BluetoothLEDevice::GetDeviceSelectorFromPairingState(true), true),
+    ];
 
-    let notifications = poll_notifications_list(&listener)?;
+    let mut found: Vec<(Option<windows::core::GUID>, BluetoothDeviceInfo)> = Vec::new();
+    for (selector, is_le) in selectors {
+        let selector = selector.map_err(|e| format!("Failed to get Bluetooth selector: {}", e))?;
 
-    let result: Vec<SystemNotification> = notifications
-        .iter()
-        .take(10)
-        .enumerate()
-        .filter_map(|(idx, notif)| extract_notification(notif, idx))
-        .collect();
+        for info in find_paired_bluetooth(&selector)? {
+            let (Ok(id), Ok(properties)) = (info.Id(), info.Properties()) else {
+                continue;
+            };
+            let name = info.Name().map(|n| n.to_string()).unwrap_or_default();
+            let connected = device_property::<bool>(&properties, AEP_IS_CONNECTED).unwrap_or(false);
+            let container_id = device_property::<windows::core::GUID>(&properties, AEP_CONTAINER_ID);
+
+            let battery = if connected {
+                is_le
+                    .then(|| gatt_battery_level(&id))
+                    .flatten()
+                    .or_else(|| container_id.and_then(device_node_battery_level))
+            } else {
+                None
+            };
 
-    Ok(result)
+            found.push((container_id, BluetoothDeviceInfo { id: id.to_string(), name, connected, battery }));
+        }
+    }
+
+    // Connected entries first so the merge keeps them over the idle half of a dual-mode device
+    found.sort_by(|(_, a), (_, b)| b.connected.cmp(&a.connected).then_with(|| a.name.cmp(&b.name)));
+
+    let mut devices: Vec<(Option<windows::core::GUID>, BluetoothDeviceInfo)> = Vec::new();
+    for (container_id, device) in found {
+        let kept = container_id.and_then(|c| devices.iter_mut().find(|(k, _)| *k == Some(c)));
+        match kept {
+            // Keep a battery level only the other half reported
+            Some((_, kept)) => kept.battery = kept.battery.or(device.battery),
+            None => devices.push((container_id, device)),
+        }
+    }
+    Ok(devices.into_iter().map(|(_, device)| device).collect())
+}
+
+/// List paired Bluetooth devices with connection state and, where available, battery level
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn list_bluetooth_devices() -> Result<Vec<BluetoothDeviceInfo>, String> {
+    spawn_com_blocking(read_bluetooth_devices).await
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn get_notifications() -> Result<Vec<SystemNotification>, String> {
-    Ok(Vec::new())
+async fn list_bluetooth_devices() -> Result<Vec<BluetoothDeviceInfo>, String> {
+    Err("Bluetooth devices not supported on this platform".to_string())
 }
 
-/// Activate (bring to foreground) the app that created the notification with the given ID.
-/// Uses the same mechanism as Windows Action Center: the app is identified by its
-/// AppUserModelId (AUMID); we launch it via the shell (explorer shell:AppsFolder\AUMID)
-/// so both UWP and desktop apps (e.g. WhatsApp) are activated correctly.
+// =============================================================================
+// Bluetooth Events
+// =============================================================================
+
+/// Paired-device watchers, kept for the app lifetime (a dropped watcher stops raising events)
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn activate_notification(id: u32) -> Result<(), String> {
-    let listener = UserNotificationListener::Current()
-        .map_err(|e| format!("Failed to get notification listener: {}", e))?;
+static BLUETOOTH_WATCHERS: Lazy<std::sync::Mutex<Vec<windows::Devices::Enumeration::DeviceWatcher>>> =
+    Lazy::new(|| std::sync::Mutex::new(Vec::new()));
 
-    let access = poll_notification_access()?;
-    if access != UserNotificationListenerAccessStatus::Allowed {
-        return Err("Notification access not granted".to_string());
+/// Watch paired classic and LE devices and emit "bluetooth-devices-changed" when one connects,
+/// disconnects, is paired or is removed. Payload is the kind of change; the frontend re-queries.
+#[cfg(target_os = "windows")]
+fn subscribe_bluetooth_changed(app_handle: &tauri::AppHandle) -> bool {
+    use std::sync::Arc;
+    use windows::Devices::Bluetooth::{BluetoothDevice, BluetoothLEDevice};
+    use windows::Devices::Enumeration::{DeviceInformation, DeviceInformationUpdate, DeviceWatcher};
+
+    let mut watchers = BLUETOOTH_WATCHERS.lock().unwrap_or_else(|e| e.into_inner());
+    if !watchers.is_empty() {
+        return true;
     }
 
-    let notifications = poll_notifications_list(&listener)?;
-    let notif = notifications
-        .iter()
-        .find(|n| n.Id().unwrap_or(0) == id)
-        .ok_or_else(|| format!("Notification {} not found", id))?;
+    let emit = |app_handle: &tauri::AppHandle, change: &str| {
+        use tauri::Emitter;
+        let _ = app_handle.emit("bluetooth-devices-changed", change);
+    };
 
-    let app_info = notif
-        .AppInfo()
-        .map_err(|e| format!("Failed to get app info: {}", e))?;
+    let start = |selector: windows::core::Result<HSTRING>| -> Result<DeviceWatcher, String> {
+        let selector = selector.map_err(|e| format!("Failed to get Bluetooth selector: {}", e))?;
+        let properties = property_list(&[AEP_IS_CONNECTED])?;
+        let watcher = DeviceInformation::CreateWatcherAqsFilterAndAdditionalProperties(&selector, &properties)
+            .map_err(|e| format!("Failed to create Bluetooth watcher: {}", e))?;
 
-    let aumid = app_info
-        .AppUserModelId()
-        .map_err(|e| format!("AppUserModelId not available: {}", e))?
-        .to_string();
-    if aumid.is_empty() {
-        return Err("AppUserModelId is empty".to_string());
-    }
+        // The initial enumeration raises Added for every paired device; only report later ones
+        let enumerated = Arc::new(AtomicBool::new(false));
 
-    // Allow the activated app to take foreground (same as when user clicks in Action Center).
-    unsafe {
-        let _ = AllowSetForegroundWindow(ASFW_ANY);
-    }
+        let (handle, flag) = (app_handle.clone(), enumerated.clone());
+        let added = TypedEventHandler::new(move |_: &Option<DeviceWatcher>, _: &Option<DeviceInformation>| {
+            if flag.load(Ordering::SeqCst) {
+                emit(&handle, "added");
+            }
+            Ok(())
+        });
 
-    // Activate via shell:AppsFolder\{AUMID}. Try two methods:
-    // 1) Open the shell path directly (lpFile = "shell:AppsFolder\AUMID")
-    // 2) If that fails, run explorer.exe with the path as argument (for desktop apps)
-    let shell_path = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
-    let result = unsafe {
-        ShellExecuteW(
-            None,
-            &HSTRING::from("open"),
-            &shell_path,
-            None,
-            None,
-            SW_SHOWNORMAL,
-        )
+        let handle = app_handle.clone();
+        let updated = TypedEventHandler::new(
+            move |_: &Option<DeviceWatcher>, update: &Option<DeviceInformationUpdate>| {
+                let connection_changed = update
+                    .as_ref()
+                    .and_then(|u| u.Properties().ok())
+                    .is_some_and(|p| p.HasKey(&HSTRING::from(AEP_IS_CONNECTED)).unwrap_or(false));
+                if connection_changed {
+                    emit(&handle, "connection");
+                }
+                Ok(())
+            },
+        );
+
+        let (handle, flag) = (app_handle.clone(), enumerated.clone());
+        let removed = TypedEventHandler::new(
+            move |_: &Option<DeviceWatcher>, _: &Option<DeviceInformationUpdate>| {
+                if flag.load(Ordering::SeqCst) {
+                    emit(&handle, "removed");
+                }
+                Ok(())
+            },
+        );
+
+        let completed = TypedEventHandler::new(move |_: &Option<DeviceWatcher>, _: &Option<windows::core::IInspectable>| {
+            enumerated.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+
+        watcher.Added(&added)
+            .and_then(|_| watcher.Updated(&updated))
+            .and_then(|_| watcher.Removed(&removed))
+            .and_then(|_| watcher.EnumerationCompleted(&completed))
+            .and_then(|_| watcher.Start())
+            .map_err(|e| format!("Failed to start Bluetooth watcher: {}", e))?;
+        Ok(watcher)
     };
-    if result.0 as isize > 32 {
-        return Ok(());
+
+    for selector in [
+        BluetoothDevice::GetDeviceSelectorFromPairingState(true),
+        BluetoothLEDevice::GetDeviceSelectorFromPairingState(true),
+    ] {
+        match start(selector) {
+            Ok(watcher) => watchers.push(watcher),
+            Err(e) => eprintln!("[PILLAR] {}", e),
+        }
     }
-    // Fallback: explorer.exe shell:AppsFolder\AUMID (some apps need this)
-    let explorer = HSTRING::from("explorer.exe");
-    let params = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
-    let result2 = unsafe {
-        ShellExecuteW(
-            None,
-            &HSTRING::from("open"),
-            &explorer,
-            &params,
-            None,
-            SW_SHOWNORMAL,
-        )
-    };
-    if result2.0 as isize <= 32 {
-        return Err(format!(
-            "Failed to activate app (ShellExecute returned {})",
-            result2.0 as isize
-        ));
+
+    if watchers.is_empty() {
+        eprintln!("[PILLAR] Failed to subscribe to Bluetooth device changes");
+        return false;
     }
-    Ok(())
+    eprintln!("[PILLAR] Successfully subscribed to Bluetooth device changes");
+    true
 }
 
-#[cfg(not(target_os = "windows"))]
-#[tauri::command]
-fn activate_notification(_id: u32) -> Result<(), String> {
-    Err("Notification activation not supported on this platform".to_string())
-}
+// =============================================================================
+// Privacy Indicators
+// =============================================================================
 
-/// Activate an app by its AUMID directly (used when notification was already dismissed from Windows).
+/// Windows records every app's mic/camera sessions here. An app whose LastUsedTimeStop is 0
+/// (with a non-zero LastUsedTimeStart) has a session open right now.
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn activate_app_by_aumid(aumid: String) -> Result<(), String> {
-    if aumid.is_empty() {
-        return Err("AUMID is empty".to_string());
-    }
+const CONSENT_STORE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\CapabilityAccessManager\\ConsentStore";
 
-    unsafe {
-        let _ = AllowSetForegroundWindow(ASFW_ANY);
-    }
+#[cfg(target_os = "windows")]
+const PRIVACY_POLL_MS: u64 = 1000;
 
-    let shell_path = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
-    let result = unsafe {
-        ShellExecuteW(
-            None,
-            &HSTRING::from("open"),
-            &shell_path,
-            None,
-            None,
-            SW_SHOWNORMAL,
-        )
+/// Helper to check whether any app currently uses a capability ("microphone" | "webcam").
+/// Packaged apps are direct subkeys; desktop apps sit one level down under NonPackaged.
+#[cfg(target_os = "windows")]
+fn capability_in_use(capability: &str) -> bool {
+    let in_use = |path: &str| {
+        let start = read_reg_qword(HKEY_CURRENT_USER, path, "LastUsedTimeStart").unwrap_or(0);
+        let stop = read_reg_qword(HKEY_CURRENT_USER, path, "LastUsedTimeStop");
+        start != 0 && stop == Some(0)
     };
-    if result.0 as isize > 32 {
-        return Ok(());
-    }
 
-    let explorer = HSTRING::from("explorer.exe");
-    let params = HSTRING::from(format!("shell:AppsFolder\\{}", aumid));
-    let result2 = unsafe {
-        ShellExecuteW(
-            None,
-            &HSTRING::from("open"),
-            &explorer,
-            &params,
-            None,
-            SW_SHOWNORMAL,
-        )
+    let root = format!("{}\\{}", CONSENT_STORE_KEY, capability);
+    let Ok(key) = open_reg_key(HKEY_CURRENT_USER, &root, KEY_READ) else {
+        return false;
     };
-    if result2.0 as isize <= 32 {
-        return Err(format!(
-            "Failed to activate app (ShellExecute returned {})",
-            result2.0 as isize
-        ));
+
+    read_reg_subkeys(&key).iter().any(|app| {
+        let path = format!("{}\\{}", root, app);
+        if app != "NonPackaged" {
+            return in_use(&path);
+        }
+        open_reg_key(HKEY_CURRENT_USER, &path, KEY_READ)
+            .map(|key| read_reg_subkeys(&key).iter().any(|exe| in_use(&format!("{}\\{}", path, exe))))
+            .unwrap_or(false)
+    })
+}
+
+/// Get whether the microphone or camera is in use by any app
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_privacy_indicators() -> PrivacyIndicators {
+    PrivacyIndicators {
+        mic_in_use: capability_in_use("microphone"),
+        camera_in_use: capability_in_use("webcam"),
     }
-    Ok(())
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn activate_app_by_aumid(_aumid: String) -> Result<(), String> {
-    Err("Not supported on this platform".to_string())
+fn get_privacy_indicators() -> PrivacyIndicators {
+    PrivacyIndicators {
+        mic_in_use: false,
+        camera_in_use: false,
+    }
 }
 
-/// Dismiss a notification by ID
+/// Emit "privacy-changed" (PrivacyIndicators) whenever the mic or camera starts or stops
+/// being used. Registry reads are cheap, so this polls like the battery watcher.
+#[cfg(target_os = "windows")]
+fn watch_privacy_indicators(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        use tauri::Emitter;
+
+        let mut last = get_privacy_indicators();
+
+        loop {
+            thread::sleep(Duration::from_millis(PRIVACY_POLL_MS));
+
+            let current = get_privacy_indicators();
+            if current != last {
+                last = current.clone();
+                let _ = app_handle.emit("privacy-changed", current);
+            }
+        }
+    });
+}
+
+// =============================================================================
+// Idle Detection
+// =============================================================================
+
+/// Idle time after which "idle-state-changed" reports the user as away (default 5 minutes)
+static IDLE_THRESHOLD_MS: AtomicU64 = AtomicU64::new(5 * 60 * 1000);
+
+#[cfg(target_os = "windows")]
+const IDLE_POLL_MS: u64 = 1000;
+
+/// Get milliseconds since the last keyboard/mouse input in this session
 #[cfg(target_os = "windows")]
 #[tauri::command]
-fn dismiss_notification(id: u32) -> Result<(), String> {
-    let listener = UserNotificationListener::Current()
-        .map_err(|e| format!("Failed to get notification listener: {}", e))?;
-    
-    listener.RemoveNotification(id)
-        .map_err(|e| format!("Failed to dismiss notification: {}", e))
+fn get_idle_time_ms() -> Result<u64, String> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return Err("Failed to get last input info".to_string());
+    }
+
+    // Both are 32-bit tick counts that wrap every ~49.7 days; the wrapping difference stays
+    // correct across a wrap as long as the idle span itself is shorter than that
+    let now = unsafe { GetTickCount() };
+    Ok(now.wrapping_sub(info.dwTime) as u64)
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn dismiss_notification(_id: u32) -> Result<(), String> {
-    Err("Notifications not supported on this platform".to_string())
+fn get_idle_time_ms() -> Result<u64, String> {
+    Err("Idle time not supported on this platform".to_string())
+}
+
+/// Set how long without input counts as idle (at least one second)
+#[tauri::command]
+fn set_idle_threshold(threshold_ms: u64) -> Result<(), String> {
+    if threshold_ms < 1000 {
+        return Err("Idle threshold must be at least 1000 ms".to_string());
+    }
+    IDLE_THRESHOLD_MS.store(threshold_ms, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Emit "idle-state-changed" (true = idle) when idle time crosses the threshold either way
+#[cfg(target_os = "windows")]
+fn watch_idle_state(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        use tauri::Emitter;
+
+        let mut was_idle = false;
+
+        loop {
+            thread::sleep(Duration::from_millis(IDLE_POLL_MS));
+
+            // Idle-driven collapse waits out a slider drag; was_idle stays put so the
+            // transition is reported once the lock is released
+            if is_interaction_locked() {
+                continue;
+            }
+
+            let Ok(idle_ms) = get_idle_time_ms() else { continue };
+            let is_idle = idle_ms >= IDLE_THRESHOLD_MS.load(Ordering::SeqCst);
+            if is_idle != was_idle {
+                was_idle = is_idle;
+                let _ = app_handle.emit("idle-state-changed", is_idle);
+            }
+        }
+    });
 }
+
 // =============================================================================
-// Auto-Start Commands
+// Clipboard History
 // =============================================================================
 
-/// Check if auto-start is enabled
+const CLIPBOARD_HISTORY_CAP: usize = 50;
+
+/// Recent text copies, newest at the back. In memory only; never written to disk.
+static CLIPBOARD_HISTORY: Lazy<std::sync::Mutex<std::collections::VecDeque<ClipboardEntry>>> =
+    Lazy::new(|| std::sync::Mutex::new(std::collections::VecDeque::with_capacity(CLIPBOARD_HISTORY_CAP)));
+
+/// Copies longer than this many UTF-16 units are not captured (default 64K)
+static CLIPBOARD_MAX_ENTRY_CHARS: AtomicUsize = AtomicUsize::new(64 * 1024);
+
+#[cfg(target_os = "windows")]
+static CLIPBOARD_WATCHER_APP: once_cell::sync::OnceCell<tauri::AppHandle> = once_cell::sync::OnceCell::new();
+
+/// Get recent clipboard text, newest first
 #[tauri::command]
-fn check_autostart_enabled(app: tauri::AppHandle) -> Result<bool, String> {
-    #[cfg(desktop)]
-    {
-        use tauri_plugin_autostart::ManagerExt;
-        app.autolaunch()
-            .is_enabled()
-            .map_err(|e| format!("Failed to check autostart status: {}", e))
+fn get_clipboard_history(limit: Option<usize>) -> Vec<ClipboardEntry> {
+    let history = CLIPBOARD_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+    history
+        .iter()
+        .rev()
+        .take(limit.unwrap_or(CLIPBOARD_HISTORY_CAP))
+        .cloned()
+        .collect()
+}
+
+#[tauri::command]
+fn clear_clipboard_history() {
+    CLIPBOARD_HISTORY.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Set the largest copy (in characters) that gets captured
+#[tauri::command]
+fn set_clipboard_max_entry_size(max_chars: usize) -> Result<(), String> {
+    if max_chars == 0 {
+        return Err("Clipboard entry size must be greater than 0".to_string());
     }
-    #[cfg(not(desktop))]
-    {
-        Ok(false)
+    CLIPBOARD_MAX_ENTRY_CHARS.store(max_chars, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Helper to read CF_UNICODETEXT from the clipboard. None when there's no text, it's too
+/// large, or the copying app asked monitors to skip it (password managers set
+/// "ExcludeClipboardContentFromMonitorProcessing").
+#[cfg(target_os = "windows")]
+fn read_clipboard_text(owner: HWND) -> Option<String> {
+    use windows::Win32::Foundation::HGLOBAL;
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard, RegisterClipboardFormatW,
+    };
+    use windows::Win32::System::Memory::{GlobalLock, GlobalSize, GlobalUnlock};
+
+    const CF_UNICODETEXT: u32 = 13;
+
+    unsafe {
+        let exclude = RegisterClipboardFormatW(windows::core::w!("ExcludeClipboardContentFromMonitorProcessing"));
+        if IsClipboardFormatAvailable(exclude).is_ok() || IsClipboardFormatAvailable(CF_UNICODETEXT).is_err() {
+            return None;
+        }
+
+        // The app that just copied may still hold the clipboard open; retry briefly
+        let mut opened = false;
+        for _ in 0..5 {
+            if OpenClipboard(owner).is_ok() {
+                opened = true;
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        if !opened {
+            return None;
+        }
+
+        let text = (|| {
+            let handle = HGLOBAL(GetClipboardData(CF_UNICODETEXT).ok()?.0);
+            let len = GlobalSize(handle) / 2;
+            if len > CLIPBOARD_MAX_ENTRY_CHARS.load(Ordering::SeqCst) + 1 {
+                return None;
+            }
+
+            let ptr = GlobalLock(handle) as *const u16;
+            if ptr.is_null() {
+                return None;
+            }
+            let wide = std::slice::from_raw_parts(ptr, len);
+            let end = wide.iter().position(|&c| c == 0).unwrap_or(len);
+            let text = String::from_utf16_lossy(&wide[..end]);
+            let _ = GlobalUnlock(handle);
+            Some(text)
+        })();
+
+        let _ = CloseClipboard();
+        text.filter(|t| !t.trim().is_empty())
     }
 }
 
-/// Enable or disable auto-start
-#[tauri::command]
-fn set_autostart_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
-    #[cfg(desktop)]
+/// Add a copy to the history and emit "clipboard-changed" (ClipboardEntry).
+/// Copying something already in the history moves it to the top instead of duplicating it.
+#[cfg(target_os = "windows")]
+fn record_clipboard_text(text: String) {
+    let entry = ClipboardEntry {
+        text,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    };
+
     {
-        use tauri_plugin_autostart::ManagerExt;
-        let autostart = app.autolaunch();
-        if enabled {
-            autostart.enable()
-                .map_err(|e| format!("Failed to enable autostart: {}", e))
-        } else {
-            autostart.disable()
-                .map_err(|e| format!("Failed to disable autostart: {}", e))
+        let mut history = CLIPBOARD_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+        history.retain(|e| e.text != entry.text);
+        if history.len() >= CLIPBOARD_HISTORY_CAP {
+            history.pop_front();
         }
+        history.push_back(entry.clone());
     }
-    #[cfg(not(desktop))]
-    {
-        Ok(())
+
+    if let Some(app) = CLIPBOARD_WATCHER_APP.get() {
+        use tauri::Emitter;
+        let _ = app.emit("clipboard-changed", entry);
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn clipboard_window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    use windows::Win32::UI::WindowsAndMessaging::{DefWindowProcW, WM_CLIPBOARDUPDATE};
+
+    if msg == WM_CLIPBOARDUPDATE {
+        if let Some(text) = read_clipboard_text(hwnd) {
+            record_clipboard_text(text);
+        }
+        return LRESULT(0);
     }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// Capture text copies into CLIPBOARD_HISTORY. Clipboard listeners need a window, so this
+/// creates a message-only window on its own thread and pumps its messages for the app lifetime.
+#[cfg(target_os = "windows")]
+fn watch_clipboard(app_handle: tauri::AppHandle) {
+    let _ = CLIPBOARD_WATCHER_APP.set(app_handle);
+
+    thread::spawn(|| unsafe {
+        use windows::Win32::System::DataExchange::AddClipboardFormatListener;
+        use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            CreateWindowExW, DispatchMessageW, GetMessageW, RegisterClassW, HWND_MESSAGE, MSG, WINDOW_EX_STYLE,
+            WINDOW_STYLE, WNDCLASSW,
+        };
+
+        let class_name = windows::core::w!("PillarClipboardWatcher");
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(clipboard_window_proc),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        if RegisterClassW(&class) == 0 {
+            eprintln!("[PILLAR] Failed to register clipboard watcher window class");
+            return;
+        }
+
+        let hwnd = match CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            windows::core::w!(""),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            None,
+            instance,
+            None,
+        ) {
+            Ok(hwnd) => hwnd,
+            Err(e) => {
+                eprintln!("[PILLAR] Failed to create clipboard watcher window: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = AddClipboardFormatListener(hwnd) {
+            eprintln!("[PILLAR] Failed to listen for clipboard changes: {}", e);
+            return;
+        }
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            DispatchMessageW(&msg);
+        }
+    });
 }
 
 // =============================================================================
-// Battery Commands
+// Screenshot Commands
 // =============================================================================
 
-/// Get battery status using Win32 GetSystemPowerStatus (no WinRT, no apartment init needed)
+/// Encoding a full 4K frame takes well over POLL_MAX_ITERS; allow ~5s
 #[cfg(target_os = "windows")]
-#[tauri::command]
-fn get_battery_info() -> Result<BatteryInfo, String> {
-    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+const SCREENSHOT_POLL_MAX_ITERS: usize = 1000;
+
+/// Helper to copy a physical-pixel rectangle of the virtual screen into top-down BGRA8 rows
+#[cfg(target_os = "windows")]
+fn capture_screen_rect(x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>, String> {
+    use windows::Win32::Graphics::Gdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, SelectObject, CAPTUREBLT, SRCCOPY,
+    };
 
     unsafe {
-        let mut sps = SYSTEM_POWER_STATUS::default();
-        GetSystemPowerStatus(&mut sps)
-            .map_err(|e| format!("Failed to get power status: {}", e))?;
+        let screen = GetDC(None);
+        if screen.is_invalid() {
+            return Err("Failed to get screen DC".to_string());
+        }
 
-        // BatteryFlag bit 128 = no system battery present
-        let has_battery = (sps.BatteryFlag & 128) == 0;
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap);
 
-        if !has_battery {
-            return Ok(BatteryInfo {
-                percent: 0,
-                is_charging: false,
-                is_battery_saver: false,
-                has_battery: false,
+        // CAPTUREBLT includes layered windows (tooltips, the island itself)
+        let copied = BitBlt(memory, 0, 0, width, height, screen, x, y, SRCCOPY | CAPTUREBLT);
+
+        SelectObject(memory, previous);
+        let _ = DeleteDC(memory);
+        ReleaseDC(None, screen);
+
+        let pixels = copied
+            .map_err(|e| format!("Failed to copy screen: {}", e))
+            .and_then(|_| {
+                read_bitmap_bgra(bitmap, width, height).ok_or_else(|| "Failed to read screen bitmap".to_string())
             });
+        let _ = DeleteObject(bitmap);
+        pixels
+    }
+}
+
+/// Capture a monitor (a name from list_available_monitors; default primary) to a PNG.
+/// Saves to `save_path` if given, otherwise to Pictures\Screenshots. Returns the saved path.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn capture_screen(
+    app: tauri::AppHandle,
+    window: tauri::Window,
+    monitor_id: Option<String>,
+    save_path: Option<String>,
+) -> Result<String, String> {
+    let monitor = match monitor_id {
+        Some(name) => window
+            .available_monitors()
+            .map_err(|e| format!("Failed to list monitors: {}", e))?
+            .into_iter()
+            .find(|m| m.name().is_some_and(|n| *n == name))
+            .ok_or_else(|| format!("Monitor {} not found", name))?,
+        None => window
+            .primary_monitor()
+            .map_err(|e| format!("Failed to get primary monitor: {}", e))?
+            .ok_or("No primary monitor")?,
+    };
+
+    let path = match save_path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            app.path()
+                .picture_dir()
+                .map_err(|e| format!("Failed to resolve Pictures folder: {}", e))?
+                .join("Screenshots")
+                .join(format!("Pillar {}.png", timestamp))
         }
+    };
 
-        // BatteryLifePercent: 0–100, or 255 when unknown
-        let percent = if sps.BatteryLifePercent == 255 {
-            0
-        } else {
-            sps.BatteryLifePercent as u32
+    let (origin, size) = (*monitor.position(), *monitor.size());
+    spawn_com_blocking(move || {
+        let mut pixels = capture_screen_rect(origin.x, origin.y, size.width as i32, size.height as i32)?;
+        // GDI leaves the alpha byte at 0
+        for px in pixels.chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+        let png = encode_png_with_limit(size.width, size.height, &pixels, SCREENSHOT_POLL_MAX_ITERS)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        std::fs::write(&path, png).map_err(|e| format!("Failed to save screenshot: {}", e))?;
+        Ok(path.to_string_lossy().into_owned())
+    })
+    .await
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+async fn capture_screen(
+    _app: tauri::AppHandle,
+    _window: tauri::Window,
+    _monitor_id: Option<String>,
+    _save_path: Option<String>,
+) -> Result<String, String> {
+    Err("Screenshots not supported on this platform".to_string())
+}
+
+// =============================================================================
+// Quick Settings Commands
+// =============================================================================
+
+#[cfg(target_os = "windows")]
+const NIGHT_LIGHT_STATE_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\CloudStore\\Store\\DefaultAccount\\Current\\default$windows.data.bluelightreduction.bluelightreductionstate\\windows.data.bluelightreduction.bluelightreductionstate";
+#[cfg(target_os = "windows")]
+const RADIO_MANAGEMENT_KEY: &str = "SYSTEM\\CurrentControlSet\\Control\\RadioManagement";
+
+/// Helper to read (wifi_on, bluetooth_on) from Windows.Devices.Radios.
+/// A radio kind with no adapter present comes back as None.
+#[cfg(target_os = "windows")]
+fn read_radio_states() -> (Option<bool>, Option<bool>) {
+    let radios = match Radio::GetRadiosAsync()
+        .map_err(|e| format!("Failed to request radios: {}", e))
+        .and_then(|op| poll_async_op(op, "radios"))
+    {
+        Ok(radios) => radios,
+        Err(e) => {
+            eprintln!("[PILLAR] {}", e);
+            return (None, None);
+        }
+    };
+
+    let mut wifi = None;
+    let mut bluetooth = None;
+    for radio in radios {
+        let (Ok(kind), Ok(state)) = (radio.Kind(), radio.State()) else {
+            continue;
         };
+        let on = state == RadioState::On;
+        // Several adapters of one kind: report on if any of them is
+        if kind == RadioKind::WiFi {
+            wifi = Some(wifi.unwrap_or(false) || on);
+        } else if kind == RadioKind::Bluetooth {
+            bluetooth = Some(bluetooth.unwrap_or(false) || on);
+        }
+    }
+    (wifi, bluetooth)
+}
 
-        // BATTERY_FLAG_CHARGING (0x08) = battery is actively receiving charge.
-        // Do NOT use ACLineStatus == 1 ("cord connected") — laptops with battery
-        // conservation modes (e.g. ASUS capped at 80%) are plugged in but NOT charging,
-        // so ACLineStatus=1 even though no current is flowing into the battery.
-        let is_charging = (sps.BatteryFlag & 0x08) != 0;
+/// Helper to read airplane mode (SystemRadioState 1 = all radios off by the user)
+#[cfg(target_os = "windows")]
+fn read_airplane_mode() -> Option<bool> {
+    read_reg_dword(HKEY_LOCAL_MACHINE, RADIO_MANAGEMENT_KEY, "SystemRadioState").map(|v| v == 1)
+}
 
-        // SystemStatusFlag bit 1 = battery saver on
-        let is_battery_saver = (sps.SystemStatusFlag & 1) != 0;
+/// Helper to read night light state from its CloudStore blob.
+/// The blob isn't documented; byte 18 is 0x15 while night light is on and 0x13 while off.
+#[cfg(target_os = "windows")]
+fn read_night_light() -> Option<bool> {
+    let data = read_reg_binary(HKEY_CURRENT_USER, NIGHT_LIGHT_STATE_KEY, "Data")?;
+    data.get(18).map(|&b| b == 0x15)
+}
 
-        Ok(BatteryInfo {
-            percent,
-            is_charging,
-            is_battery_saver,
-            has_battery: true,
+/// Helper to read Focus assist / Do not disturb (any active profile = on, whether it was
+/// turned on manually or by a schedule/rule)
+#[cfg(target_os = "windows")]
+fn read_focus_assist() -> Option<bool> {
+    get_focus_assist_state().ok().map(|profile| profile != "off")
+}
+
+/// Get every quick-settings toggle in one call for the quick-settings panel.
+/// Radio, WMI and DDC/CI reads block, so the snapshot is taken off the main thread.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn get_quick_settings() -> Result<QuickSettings, String> {
+    // One COM/WinRT init for the whole snapshot; the volume/radio calls below reuse it
+    spawn_com_blocking(|| {
+        let (wifi_enabled, bluetooth_enabled) = read_radio_states();
+        let battery = get_battery_info().ok().filter(|b| b.has_battery);
+
+        Ok(QuickSettings {
+            wifi_enabled,
+            bluetooth_enabled,
+            airplane_mode: read_airplane_mode(),
+            night_light: read_night_light(),
+            focus_assist: read_focus_assist(),
+            battery_saver: battery.map(|b| b.is_battery_saver),
+            volume: get_system_volume(None).ok(),
+            brightness: get_system_brightness().ok().filter(|b| b.is_supported),
         })
-    }
+    })
+    .await
 }
 
 #[cfg(not(target_os = "windows"))]
 #[tauri::command]
-fn get_battery_info() -> Result<BatteryInfo, String> {
-    Ok(BatteryInfo {
-        percent: 0,
-        is_charging: false,
-        is_battery_saver: false,
-        has_battery: false,
+fn get_quick_settings() -> Result<QuickSettings, String> {
+    Ok(QuickSettings {
+        wifi_enabled: None,
+        bluetooth_enabled: None,
+        airplane_mode: None,
+        night_light: None,
+        focus_assist: None,
+        battery_saver: None,
+        volume: None,
+        brightness: None,
     })
 }
 
@@ -2053,41 +9486,152 @@ pub fn run() {
     builder
         .invoke_handler(tauri::generate_handler![
             set_click_through,
+            set_hit_test_region,
+            set_window_visible,
+            is_window_visible,
+            set_always_on_top,
+            set_window_z_order,
             resize_window,
             position_window,
+            position_window_on,
+            set_top_edge_hot_zone,
+            set_top_edge_hover_enabled,
+            list_available_monitors,
+            get_work_area,
+            get_taskbar_info,
+            save_window_position,
+            reset_window_position,
             resize_and_center,
+            animate_resize,
             is_foreground_fullscreen,
+            get_foreground_app,
             get_scale_factor,
+            set_interaction_lock,
+            get_foreground_window_color,
             // Media session
             get_media_session,
+            list_media_sessions,
             media_play_pause,
             media_next,
             media_previous,
+            media_stop,
+            media_seek,
+            media_skip_relative,
+            media_set_shuffle,
+            media_set_repeat,
+            media_play_pause_for,
+            media_next_for,
+            media_previous_for,
+            get_media_theme,
+            get_media_thumbnail,
             // Volume control
             get_system_volume,
             set_system_volume,
             toggle_mute,
+            adjust_system_volume,
+            volume_step,
+            fade_system_volume,
+            get_master_peak,
+            get_volume_balance,
+            set_volume_balance,
+            // Microphone
+            get_mic_volume,
+            set_mic_volume,
+            toggle_mic_mute,
             // Audio devices
             list_audio_devices,
             get_default_audio_device,
+            list_input_devices,
+            get_device_volume,
+            set_device_volume,
+            get_device_channels,
+            set_default_audio_device,
+            cycle_default_output,
             // Per-app volume
             list_audio_sessions,
             set_session_volume,
             set_session_mute,
+            get_media_session_volume,
+            set_app_volume,
+            get_session_peaks,
+            solo_audio_session,
+            unsolo_audio_sessions,
+            get_session_icon,
             // Brightness control
             get_system_brightness,
             set_system_brightness,
+            fade_system_brightness,
+            list_monitors,
+            get_monitor_brightness,
+            set_monitor_brightness,
+            set_all_monitors_brightness,
+            get_monitor_contrast,
+            set_monitor_contrast,
+            ddc_get_vcp,
+            ddc_set_vcp,
+            // Color temperature
+            set_color_temperature,
+            reset_color_temperature,
             // Notifications
             check_notification_access,
+            open_notification_settings,
             get_notifications,
+            get_notification_count,
             dismiss_notification,
+            clear_all_notifications,
+            get_focus_assist_state,
+            get_notification_history,
+            snooze_notification,
+            get_notification_app_filter,
+            set_notification_app_filter,
             activate_notification,
             activate_app_by_aumid,
             // Auto-start
             check_autostart_enabled,
             set_autostart_enabled,
+            // Elevation
+            is_elevated,
+            relaunch_as_admin,
+            // Startup apps
+            list_startup_apps,
+            set_startup_app_enabled,
+            // Accessibility
+            get_cursor_size,
+            set_cursor_size,
+            get_tablet_mode,
+            get_display_topology,
+            // Power plans
+            list_power_plans,
+            get_power_plan,
+            set_power_plan,
+            get_power_mode,
+            set_power_mode,
             // Battery
             get_battery_info,
+            get_battery_status,
+            get_battery_low_thresholds,
+            set_battery_low_thresholds,
+            get_battery_saver,
+            set_battery_saver,
+            // System stats
+            get_system_stats,
+            // Network
+            get_network_status,
+            // Bluetooth
+            list_bluetooth_devices,
+            // Privacy indicators
+            get_privacy_indicators,
+            // Idle detection
+            get_idle_time_ms,
+            set_idle_threshold,
+            // Clipboard history
+            get_clipboard_history,
+            clear_clipboard_history,
+            set_clipboard_max_entry_size,
+            // Screenshots
+            capture_screen,
+            // Quick settings
+            get_quick_settings,
             // Prism AI
             prism_chat
         ])
@@ -2104,6 +9648,7 @@ pub fn run() {
                     .show_menu_on_left_click(true)
                     .on_menu_event(move |app, event| {
                         if event.id.as_ref() == "quit" {
+                            stop_top_edge_hover();
                             app.exit(0);
                         }
                     });
@@ -2117,22 +9662,61 @@ pub fn run() {
                     .map_err(|e| e.to_string())?;
 
                 // Window positioning is a desktop API; ignore failures.
+                // A position the user saved wins over the default top-center.
+                load_saved_window_position(app.handle());
                 if let Some(window) = app.get_webview_window("main") {
-                    if let Ok(Some(monitor)) = window.primary_monitor() {
-                        let monitor_size = monitor.size();
-                        let scale_factor = monitor.scale_factor();
-                        let window_width = 450.0;
-                        let x = (monitor_size.width as f64 / scale_factor) / 2.0 - window_width / 2.0;
-                        let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition {
-                            x,
-                            y: 0.0,
-                        }));
+                    if !restore_window_position(&window) {
+                        if let Ok(Some(monitor)) = window.primary_monitor() {
+                            let monitor_size = monitor.size();
+                            let scale_factor = monitor.scale_factor();
+                            let window_width = 450.0;
+                            let x = (monitor_size.width as f64 / scale_factor) / 2.0 - window_width / 2.0;
+                            let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition {
+                                x,
+                                y: 0.0,
+                            }));
+                        }
                     }
                 }
             }
 
+            load_notification_app_filter(app.handle());
+
             #[cfg(target_os = "windows")]
             {
+                prewarm_runtime();
+
+                // SMTC lookups block for a moment; subscribe off the setup thread
+                let media_app_handle = app.handle().clone();
+                thread::spawn(move || {
+                    let _com = ComGuard::new();
+                    let _ = subscribe_media_changed(&media_app_handle);
+                    let _ = subscribe_audio_devices_changed(&media_app_handle);
+                    let _ = subscribe_audio_sessions_changed(&media_app_handle);
+                    let _ = subscribe_network_changed(&media_app_handle);
+                    let _ = subscribe_bluetooth_changed(&media_app_handle);
+                });
+
+                watch_brightness_changes(app.handle().clone());
+                watch_top_edge_hover(app.handle().clone());
+                watch_battery_changes(app.handle().clone());
+                watch_system_stats(app.handle().clone());
+                watch_network_changes(app.handle().clone());
+                watch_privacy_indicators(app.handle().clone());
+                watch_idle_state(app.handle().clone());
+                watch_clipboard(app.handle().clone());
+                watch_power_plan_changes(app.handle().clone());
+
+                if let Some(window) = app.get_webview_window("main") {
+                    if let Err(e) = install_window_message_hook(&window) {
+                        eprintln!("[PILLAR] {}", e);
+                    }
+                }
+
+                if let Err(e) = install_foreground_hook(app.handle()) {
+                    eprintln!("[PILLAR] {}", e);
+                }
+
                 match UserNotificationListener::Current() {
                     Ok(listener) => {
                         match poll_notification_access() {
@@ -2162,3 +9746,69 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_session_calls_reuse_the_session_manager() {
+        let _com = ComGuard::new();
+        *SESSION_MANAGER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        let before = SESSION_MANAGER_REQUESTS.load(Ordering::SeqCst);
+
+        let first = std::time::Instant::now();
+        get_media_session().expect("first get_media_session");
+        let first = first.elapsed();
+
+        let repeated = std::time::Instant::now();
+        for _ in 0..20 {
+            get_media_session().expect("repeated get_media_session");
+        }
+        let repeated = repeated.elapsed() / 20;
+
+        // Only the first call pays for RequestAsync and its poll loop
+        assert_eq!(SESSION_MANAGER_REQUESTS.load(Ordering::SeqCst) - before, 1);
+        assert!(repeated < first, "cached {:?} per call vs first {:?}", repeated, first);
+    }
+
+    #[test]
+    fn brightness_calls_reuse_the_physical_monitors() {
+        let _com = ComGuard::new();
+        // Desktops without DDC/CI, VMs and CI runners have nothing to set
+        let info = get_system_brightness().expect("get_system_brightness");
+        if info.backend == "none" {
+            return;
+        }
+
+        // Start from an empty cache (get_system_brightness may have filled it)
+        close_physical_monitors(&mut PHYSICAL_MONITOR_CACHE.lock().unwrap_or_else(|e| e.into_inner()));
+        let opens_before = PHYSICAL_MONITOR_OPENS.load(Ordering::SeqCst);
+        let destroys_before = PHYSICAL_MONITOR_DESTROYS.load(Ordering::SeqCst);
+
+        // Re-apply the current level so the test doesn't visibly change the display
+        let first = std::time::Instant::now();
+        set_system_brightness(info.level).expect("first set_system_brightness");
+        let first = first.elapsed();
+
+        let repeated = std::time::Instant::now();
+        for _ in 0..20 {
+            set_system_brightness(info.level).expect("repeated set_system_brightness");
+        }
+        let repeated = repeated.elapsed() / 20;
+
+        // WMI panels never open a physical monitor; DDC/CI opens it once
+        let opens = PHYSICAL_MONITOR_OPENS.load(Ordering::SeqCst) - opens_before;
+        assert!(opens <= 1, "opened physical monitors {} times", opens);
+        if opens == 1 {
+            // Only the first call paid for opening the monitor
+            assert!(repeated < first, "cached {:?} per call vs first {:?}", repeated, first);
+        }
+
+        // Invalidation closes what was cached and the next call opens it again
+        invalidate_physical_monitors();
+        set_system_brightness(info.level).expect("set_system_brightness after invalidation");
+        assert_eq!(PHYSICAL_MONITOR_DESTROYS.load(Ordering::SeqCst) - destroys_before, opens);
+        assert_eq!(PHYSICAL_MONITOR_OPENS.load(Ordering::SeqCst) - opens_before, opens * 2);
+    }
+}
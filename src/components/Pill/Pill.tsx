@@ -102,7 +102,7 @@ export function Pill() {
     playPause,
     next: mediaNext,
     previous: mediaPrevious,
-  } = useMediaSession(1500); // Poll every 1.5s (reduced from 600ms to avoid saturating backend)
+  } = useMediaSession(5000); // Fallback poll; "media-changed" events carry the real-time updates
 
   // Volume hook
   const {
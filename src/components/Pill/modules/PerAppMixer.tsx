@@ -9,8 +9,8 @@ import { microInteractions } from "../animations";
 
 interface AppVolumeSliderProps {
   session: AudioSession;
-  onVolumeChange: (processId: number, volume: number) => void;
-  onMuteToggle: (processId: number, muted: boolean) => void;
+  onVolumeChange: (sessionId: string, volume: number) => void;
+  onMuteToggle: (sessionId: string, muted: boolean) => void;
 }
 
 function AppVolumeSlider({ session, onVolumeChange, onMuteToggle }: AppVolumeSliderProps) {
@@ -24,10 +24,10 @@ function AppVolumeSlider({ session, onVolumeChange, onMuteToggle }: AppVolumeSli
 
   const handleMouseUp = useCallback(() => {
     if (isDragging) {
-      onVolumeChange(session.processId, localVolume);
+      onVolumeChange(session.sessionId, localVolume);
       setIsDragging(false);
     }
-  }, [isDragging, localVolume, onVolumeChange, session.processId]);
+  }, [isDragging, localVolume, onVolumeChange, session.sessionId]);
 
   const handleMouseDown = useCallback(() => {
     setIsDragging(true);
@@ -68,11 +68,11 @@ function AppVolumeSlider({ session, onVolumeChange, onMuteToggle }: AppVolumeSli
             }`}
             aria-label={`${session.isMuted ? "Unmute" : "Mute"} ${session.appName}`}
             {...microInteractions.icon}
-            onClick={() => onMuteToggle(session.processId, !session.isMuted)}
+            onClick={() => onMuteToggle(session.sessionId, !session.isMuted)}
             onKeyDown={(e) => {
               if (e.key === "Enter" || e.key === " ") {
                 e.preventDefault();
-                onMuteToggle(session.processId, !session.isMuted);
+                onMuteToggle(session.sessionId, !session.isMuted);
               }
             }}
           >
@@ -142,8 +142,8 @@ function AppVolumeSlider({ session, onVolumeChange, onMuteToggle }: AppVolumeSli
 
 interface PerAppMixerProps {
   sessions: AudioSession[];
-  onVolumeChange: (processId: number, volume: number) => void;
-  onMuteToggle: (processId: number, muted: boolean) => void;
+  onVolumeChange: (sessionId: string, volume: number) => void;
+  onMuteToggle: (sessionId: string, muted: boolean) => void;
 }
 
 export function PerAppMixer({ sessions, onVolumeChange, onMuteToggle }: PerAppMixerProps) {
@@ -162,7 +162,7 @@ export function PerAppMixer({ sessions, onVolumeChange, onMuteToggle }: PerAppMi
     <div className="flex flex-col gap-0.5 max-h-24 overflow-y-auto pr-0.5">
       {sessions.map((session) => (
         <AppVolumeSlider
-          key={session.processId}
+          key={session.sessionId}
           session={session}
           onVolumeChange={onVolumeChange}
           onMuteToggle={onMuteToggle}
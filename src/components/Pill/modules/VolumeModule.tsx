@@ -431,8 +431,8 @@ interface QuickSettingsProps {
   audioDevices: AudioDevice[];
   defaultAudioDevice: AudioDevice | null;
   audioSessions: AudioSession[];
-  onSessionVolumeChange: (processId: number, volume: number) => void;
-  onSessionMuteToggle: (processId: number, muted: boolean) => void;
+  onSessionVolumeChange: (sessionId: string, volume: number) => void;
+  onSessionMuteToggle: (sessionId: string, muted: boolean) => void;
   autoStartEnabled: boolean;
   onAutoStartToggle: () => void;
   appearance: AppearanceControls;
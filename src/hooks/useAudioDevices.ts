@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { tauriInvoke } from "../lib/tauri";
 
 // =============================================================================
@@ -9,6 +10,9 @@ export interface AudioDevice {
   id: string;
   name: string;
   isDefault: boolean;
+  formFactor: string;
+  iconPath: string | null;
+  state: "active" | "disabled" | "unplugged" | "notpresent";
 }
 
 interface UseAudioDevicesReturn {
@@ -39,6 +43,9 @@ export function useAudioDevices(pollInterval = 5000): UseAudioDevicesReturn {
         id: string;
         name: string;
         is_default: boolean;
+        form_factor: string;
+        icon_path: string | null;
+        state: AudioDevice["state"];
       }>>("list_audio_devices");
 
       if (result) {
@@ -46,6 +53,9 @@ export function useAudioDevices(pollInterval = 5000): UseAudioDevicesReturn {
           id: d.id,
           name: d.name,
           isDefault: d.is_default,
+          formFactor: d.form_factor,
+          iconPath: d.icon_path,
+          state: d.state,
         }));
         setDevices(mapped);
 
@@ -100,6 +110,23 @@ export function useAudioDevices(pollInterval = 5000): UseAudioDevicesReturn {
     };
   }, [fetchDevices, pollInterval]);
 
+  // Real-time: device added/removed/state or default changed -> re-query
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: (() => void) | null = null;
+
+    listen("audio-devices-changed", () => {
+      if (isMounted) fetchDevices();
+    }).then((fn) => {
+      if (isMounted) unlisten = fn; else fn();
+    }).catch(() => {});
+
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
+  }, [fetchDevices]);
+
   return {
     devices,
     defaultDevice,
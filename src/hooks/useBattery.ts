@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { tauriInvoke } from "../lib/tauri";
 
 // =============================================================================
@@ -10,8 +11,28 @@ export interface BatteryInfo {
   isCharging: boolean;
   isBatterySaver: boolean;
   hasBattery: boolean;
+  timeRemainingMinutes: number | null; // null while charging or still estimating
+  powerSource: "ac" | "battery" | "unknown";
 }
 
+interface RawBatteryInfo {
+  percent: number;
+  is_charging: boolean;
+  is_battery_saver: boolean;
+  has_battery: boolean;
+  time_remaining_minutes: number | null;
+  power_source: BatteryInfo["powerSource"];
+}
+
+const mapBattery = (b: RawBatteryInfo): BatteryInfo => ({
+  percent: b.percent,
+  isCharging: b.is_charging,
+  isBatterySaver: b.is_battery_saver,
+  hasBattery: b.has_battery,
+  timeRemainingMinutes: b.time_remaining_minutes,
+  powerSource: b.power_source,
+});
+
 interface UseBatteryReturn {
   battery: BatteryInfo;
   isLow: boolean;        // true when <= 15%
@@ -28,6 +49,8 @@ export function useBattery(pollInterval = 60000): UseBatteryReturn {
     isCharging: false,
     isBatterySaver: false,
     hasBattery: false,
+    timeRemainingMinutes: null,
+    powerSource: "unknown",
   });
 
   const pollIntervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
@@ -37,19 +60,9 @@ export function useBattery(pollInterval = 60000): UseBatteryReturn {
     if (isPendingRef.current) return;
     isPendingRef.current = true;
     try {
-      const result = await tauriInvoke<{
-        percent: number;
-        is_charging: boolean;
-        is_battery_saver: boolean;
-        has_battery: boolean;
-      }>("get_battery_info");
+      const result = await tauriInvoke<RawBatteryInfo>("get_battery_info");
       if (result) {
-        setBattery({
-          percent: result.percent,
-          isCharging: result.is_charging,
-          isBatterySaver: result.is_battery_saver,
-          hasBattery: result.has_battery,
-        });
+        setBattery(mapBattery(result));
       }
     } catch {
       // Silently handle errors
@@ -101,6 +114,23 @@ export function useBattery(pollInterval = 60000): UseBatteryReturn {
     };
   }, [fetchBattery, pollInterval]);
 
+  // Real-time: plug/unplug and level changes pushed from the backend
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: (() => void) | null = null;
+
+    listen<RawBatteryInfo>("battery-changed", (event) => {
+      if (isMounted) setBattery(mapBattery(event.payload));
+    }).then((fn) => {
+      if (isMounted) unlisten = fn; else fn();
+    }).catch(() => {});
+
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
+  }, []);
+
   return {
     battery,
     isLow: battery.hasBattery && !battery.isCharging && battery.percent <= 15,
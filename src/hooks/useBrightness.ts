@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { tauriInvoke } from "../lib/tauri";
 
 // =============================================================================
@@ -10,6 +11,7 @@ export interface BrightnessInfo {
   min: number;        // minimum brightness level
   max: number;        // maximum brightness level
   isSupported: boolean;
+  backend: "wmi" | "ddcci" | "none"; // DDC/CI can be unreliable on some monitors
 }
 
 interface UseBrightnessReturn {
@@ -29,6 +31,7 @@ export function useBrightness(pollInterval = 10000): UseBrightnessReturn {
     min: 0,
     max: 100,
     isSupported: false,
+    backend: "none",
   });
   const [isLoading, setIsLoading] = useState(false);
 
@@ -48,6 +51,7 @@ export function useBrightness(pollInterval = 10000): UseBrightnessReturn {
         min: number;
         max: number;
         is_supported: boolean;
+        backend: BrightnessInfo["backend"];
       }>("get_system_brightness");
 
       if (result) {
@@ -56,6 +60,7 @@ export function useBrightness(pollInterval = 10000): UseBrightnessReturn {
           min: result.min,
           max: result.max,
           isSupported: result.is_supported,
+          backend: result.backend,
         });
       }
     } catch {
@@ -119,6 +124,39 @@ export function useBrightness(pollInterval = 10000): UseBrightnessReturn {
     };
   }, [fetchBrightness, pollInterval]);
 
+  // Real-time: Fn keys / auto-dim changes pushed from the backend
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: (() => void) | null = null;
+
+    listen<{
+      level: number;
+      min: number;
+      max: number;
+      is_supported: boolean;
+      backend: BrightnessInfo["backend"];
+    }>("brightness-changed", (event) => {
+      if (!isMounted) return;
+      // Our own slider drag also triggers this; don't fight it
+      if (Date.now() < suppressPollUntilRef.current) return;
+      const b = event.payload;
+      setBrightnessState({
+        level: b.level,
+        min: b.min,
+        max: b.max,
+        isSupported: b.is_supported,
+        backend: b.backend,
+      });
+    }).then((fn) => {
+      if (isMounted) unlisten = fn; else fn();
+    }).catch(() => {});
+
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
+  }, []);
+
   return {
     brightness,
     isLoading,
@@ -1,16 +1,43 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { tauriInvoke } from "../lib/tauri";
 
 // =============================================================================
 // Types
 // =============================================================================
 
+export interface MediaControls {
+  playPause: boolean;
+  play: boolean;
+  pause: boolean;
+  stop: boolean;
+  next: boolean;
+  previous: boolean;
+  fastForward: boolean;
+  rewind: boolean;
+  shuffle: boolean;
+  repeat: boolean;
+  seek: boolean;
+}
+
 export interface MediaInfo {
+  sessionId: string; // Source app AUMID
   title: string;
   artist: string;
   album?: string;
   isPlaying: boolean;
   appName?: string;
+  positionMs: number;
+  durationMs: number; // 0 for live/streaming content
+  lastUpdatedMs: number; // Unix ms when positionMs was reported
+  shuffle?: boolean;
+  repeatMode?: "none" | "track" | "list";
+  playbackRate: number; // 1.0 = normal speed
+  controls: MediaControls;
+  genres: string[];
+  trackNumber?: number;
+  albumTrackCount?: number;
+  contentType: "music" | "video" | "image" | "unknown";
 }
 
 interface UseMediaSessionReturn {
@@ -24,11 +51,72 @@ interface UseMediaSessionReturn {
 }
 
 interface RawMediaInfo {
+  session_id: string;
   title: string;
   artist: string;
   album?: string;
   is_playing: boolean;
   app_name?: string;
+  position_ms: number;
+  duration_ms: number;
+  last_updated_ms: number;
+  shuffle?: boolean | null;
+  repeat_mode?: "none" | "track" | "list" | null;
+  playback_rate: number;
+  controls?: {
+    play_pause: boolean;
+    play: boolean;
+    pause: boolean;
+    stop: boolean;
+    next: boolean;
+    previous: boolean;
+    fast_forward: boolean;
+    rewind: boolean;
+    shuffle: boolean;
+    repeat: boolean;
+    seek: boolean;
+  };
+  genres?: string[];
+  track_number?: number | null;
+  album_track_count?: number | null;
+  content_type?: "music" | "video" | "image" | "unknown";
+}
+
+// Transform snake_case to camelCase
+function toMediaInfo(raw: RawMediaInfo | null): MediaInfo | null {
+  return raw
+    ? {
+        sessionId: raw.session_id || "",
+        title: raw.title || "",
+        artist: raw.artist || "",
+        album: raw.album || undefined,
+        isPlaying: raw.is_playing || false,
+        appName: raw.app_name || undefined,
+        positionMs: raw.position_ms || 0,
+        durationMs: raw.duration_ms || 0,
+        lastUpdatedMs: raw.last_updated_ms || 0,
+        shuffle: raw.shuffle ?? undefined,
+        repeatMode: raw.repeat_mode ?? undefined,
+        playbackRate: raw.playback_rate || 1,
+        controls: {
+          playPause: raw.controls?.play_pause ?? true,
+          play: raw.controls?.play ?? false,
+          pause: raw.controls?.pause ?? false,
+          stop: raw.controls?.stop ?? false,
+          next: raw.controls?.next ?? false,
+          previous: raw.controls?.previous ?? false,
+          fastForward: raw.controls?.fast_forward ?? false,
+          rewind: raw.controls?.rewind ?? false,
+          shuffle: raw.controls?.shuffle ?? false,
+          repeat: raw.controls?.repeat ?? false,
+          seek: raw.controls?.seek ?? false,
+        },
+        genres: raw.genres ?? [],
+        trackNumber: raw.track_number ?? undefined,
+        albumTrackCount: raw.album_track_count ?? undefined,
+        contentType: raw.content_type ?? "unknown",
+      }
+    : null;
 }
 
 // =============================================================================
@@ -36,7 +124,7 @@ interface RawMediaInfo {
 // =============================================================================
 
 export function useMediaSession(
-  pollInterval = 600,
+  pollInterval = 5000,
   onMediaChange?: (media: MediaInfo | null) => void
 ): UseMediaSessionReturn {
   const [media, setMedia] = useState<MediaInfo | null>(null);
@@ -54,17 +142,7 @@ export function useMediaSession(
     isPendingRef.current = true;
     try {
       const result = await tauriInvoke<RawMediaInfo | null>("get_media_session");
-
-      // Transform snake_case to camelCase
-      const transformed = result
-        ? {
-            title: result.title || "",
-            artist: result.artist || "",
-            album: result.album || undefined,
-            isPlaying: result.is_playing || false,
-            appName: result.app_name || undefined,
-          }
-        : null;
+      const transformed = toMediaInfo(result);
 
       setMedia(transformed);
       setError(null);
@@ -135,6 +213,30 @@ export function useMediaSession(
     };
   }, [fetchMedia, pollInterval]);
 
+  // Real-time: SMTC session/track/playback changes arrive as "media-changed"; the poll is a fallback
+  useEffect(() => {
+    let isMounted = true;
+    let unlisten: (() => void) | null = null;
+
+    listen<RawMediaInfo | null>("media-changed", (event) => {
+      if (!isMounted) return;
+      const transformed = toMediaInfo(event.payload);
+      setMedia(transformed);
+      setError(null);
+
+      if (onMediaChangeRef.current) {
+        onMediaChangeRef.current(transformed);
+      }
+    }).then((fn) => {
+      if (isMounted) unlisten = fn; else fn();
+    }).catch(() => {});
+
+    return () => {
+      isMounted = false;
+      unlisten?.();
+    };
+  }, []);
+
   return {
     media,
     isLoading,
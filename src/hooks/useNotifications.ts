@@ -13,6 +13,7 @@ export interface SystemNotification {
   body: string;
   timestamp: number;  // Unix timestamp in milliseconds
   aumid?: string;     // App User Model ID for direct activation
+  appLogo?: string;   // Data URL of the sender's logo
 }
 
 // Animation phase for notification flow
@@ -26,6 +27,8 @@ interface UseNotificationsReturn {
   notificationPhase: NotificationPhase;
   isNewNotification: boolean;  // True when a new notification just arrived (for pulse animation)
   dismissNotification: (id: number) => Promise<void>;
+  snoozeNotification: (id: number, minutes: number) => Promise<void>;
+  openNotificationSettings: () => Promise<void>;
   refresh: () => Promise<void>;
   clearLatest: () => void;
 }
@@ -116,6 +119,7 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
         body: string;
         timestamp: number;
         aumid: string | null;
+        app_logo: string | null;
       }>>("get_notifications");
 
       if (result) {
@@ -126,6 +130,7 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
           body: n.body,
           timestamp: n.timestamp,
           aumid: n.aumid ?? undefined,
+          appLogo: n.app_logo ?? undefined,
         }));
 
         // Check for new notifications
@@ -173,6 +178,26 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
     }
   }, [latestNotification]);
 
+  // Snooze: hide now, the backend re-emits it after the delay
+  const snoozeNotification = useCallback(async (id: number, minutes: number) => {
+    await tauriInvoke("snooze_notification", { id, minutes });
+    setNotifications(prev => {
+      const updated = prev.filter(n => n.id !== id);
+      if (updated.length === 0) {
+        setNotificationPhase("idle");
+      }
+      return updated;
+    });
+    if (latestNotification?.id === id) {
+      setLatestNotification(null);
+    }
+  }, [latestNotification]);
+
+  // Deep-link to the Settings page that grants notification access
+  const openNotificationSettings = useCallback(async () => {
+    await tauriInvoke("open_notification_settings");
+  }, []);
+
   // Clear latest notification (user dismissed toast early)
   const clearLatest = useCallback(() => {
     setLatestNotification(null);
@@ -192,6 +217,8 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
 
   const unlistenChangedRef = useRef<(() => void) | null>(null);
   const unlistenAddedRef = useRef<(() => void) | null>(null);
+  const unlistenRemovedRef = useRef<(() => void) | null>(null);
+  const unlistenSnoozedRef = useRef<(() => void) | null>(null);
 
   // Real-time: listen for Windows notification events from backend; fallback poll as backup
   useEffect(() => {
@@ -225,6 +252,7 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
         body: string;
         timestamp: number;
         aumid: string | null;
+        app_logo: string | null;
       }>("notification-added", (event) => {
         if (!isMounted) return;
         const n = event.payload;
@@ -235,6 +263,7 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
           body: n.body,
           timestamp: n.timestamp,
           aumid: n.aumid ?? undefined,
+          appLogo: n.app_logo ?? undefined,
         };
 
         // Add to notifications list (dedupe by ID, prepend)
@@ -251,7 +280,50 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
         if (isMounted) unlistenAddedRef.current = fn;
       }).catch(() => {});
 
-      // Fallback: generic change event (for removals, or if interception failed)
+      // Snooze timer elapsed: bring the notification back as if it just arrived
+      listen<{
+        id: number;
+        app_name: string;
+        title: string;
+        body: string;
+        timestamp: number;
+        aumid: string | null;
+        app_logo: string | null;
+      }>("notification-snoozed-return", (event) => {
+        if (!isMounted) return;
+        const n = event.payload;
+        const mapped: SystemNotification = {
+          id: n.id,
+          appName: n.app_name,
+          title: n.title,
+          body: n.body,
+          timestamp: n.timestamp,
+          aumid: n.aumid ?? undefined,
+          appLogo: n.app_logo ?? undefined,
+        };
+
+        setNotifications(prev => [mapped, ...prev.filter(p => p.id !== mapped.id)].slice(0, 10));
+        triggerNotificationAnimation(mapped);
+      }).then((fn) => {
+        if (isMounted) unlistenSnoozedRef.current = fn;
+      }).catch(() => {});
+
+      // Dismissed from Action Center (or by another app): drop just that entry
+      listen<number>("notification-removed", (event) => {
+        if (!isMounted) return;
+        const id = event.payload;
+        setNotifications(prev => {
+          const updated = prev.filter(n => n.id !== id);
+          if (updated.length === 0 && notificationPhaseRef.current === "showing") {
+            setNotificationPhase("idle");
+          }
+          return updated;
+        });
+      }).then((fn) => {
+        if (isMounted) unlistenRemovedRef.current = fn;
+      }).catch(() => {});
+
+      // Fallback: generic change event (if the backend couldn't read the list)
       listen("notification-changed", () => {
         if (isMounted) fetchNotifications();
       }).then((fn) => {
@@ -284,6 +356,10 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
       unlistenChangedRef.current = null;
       unlistenAddedRef.current?.();
       unlistenAddedRef.current = null;
+      unlistenRemovedRef.current?.();
+      unlistenRemovedRef.current = null;
+      unlistenSnoozedRef.current?.();
+      unlistenSnoozedRef.current = null;
       document.removeEventListener("visibilitychange", onVisibilityChange);
     };
   }, [pollInterval, fetchNotifications, triggerNotificationAnimation]);
@@ -296,6 +372,8 @@ export function useNotifications(pollInterval = FALLBACK_POLL_MS): UseNotificati
     notificationPhase,
     isNewNotification,
     dismissNotification,
+    snoozeNotification,
+    openNotificationSettings,
     refresh,
     clearLatest,
   };
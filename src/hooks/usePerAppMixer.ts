@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { tauriInvoke } from "../lib/tauri";
 
 // =============================================================================
@@ -12,13 +13,15 @@ export interface AudioSession {
   volume: number;        // 0.0 - 1.0
   isMuted: boolean;
   isActive: boolean;
+  state: "active" | "inactive" | "expired";
+  peak: number;          // 0.0 - 1.0
 }
 
 interface UsePerAppMixerReturn {
   sessions: AudioSession[];
   isLoading: boolean;
-  setSessionVolume: (processId: number, volume: number) => Promise<void>;
-  setSessionMute: (processId: number, muted: boolean) => Promise<void>;
+  setSessionVolume: (sessionId: string, volume: number) => Promise<void>;
+  setSessionMute: (sessionId: string, muted: boolean) => Promise<void>;
   refresh: () => Promise<void>;
 }
 
@@ -45,6 +48,8 @@ export function usePerAppMixer(pollInterval = 3000): UsePerAppMixerReturn {
         volume: number;
         is_muted: boolean;
         is_active: boolean;
+        state: AudioSession["state"];
+        peak: number;
       }>>("list_audio_sessions");
 
       if (result) {
@@ -55,6 +60,8 @@ export function usePerAppMixer(pollInterval = 3000): UsePerAppMixerReturn {
           volume: s.volume,
           isMuted: s.is_muted,
           isActive: s.is_active,
+          state: s.state,
+          peak: s.peak,
         })));
       }
     } catch {
@@ -70,26 +77,26 @@ export function usePerAppMixer(pollInterval = 3000): UsePerAppMixerReturn {
     await fetchSessions();
   }, [fetchSessions]);
 
-  // Set session volume
-  const setSessionVolume = useCallback(async (processId: number, volume: number) => {
+  // Set session volume (by session instance ID; one process can own several sessions)
+  const setSessionVolume = useCallback(async (sessionId: string, volume: number) => {
     const clampedVolume = Math.max(0, Math.min(1, volume));
-    await tauriInvoke("set_session_volume", { processId, level: clampedVolume });
+    await tauriInvoke("set_session_volume", { sessionId, level: clampedVolume });
     
     // Update local state optimistically
     setSessions(prev => prev.map(s => 
-      s.processId === processId 
+      s.sessionId === sessionId 
         ? { ...s, volume: clampedVolume }
         : s
     ));
   }, []);
 
   // Set session mute
-  const setSessionMute = useCallback(async (processId: number, muted: boolean) => {
-    await tauriInvoke("set_session_mute", { processId, muted });
+  const setSessionMute = useCallback(async (sessionId: string, muted: boolean) => {
+    await tauriInvoke("set_session_mute", { sessionId, muted });
     
     // Update local state optimistically
     setSessions(prev => prev.map(s => 
-      s.processId === processId 
+      s.sessionId === sessionId 
         ? { ...s, isMuted: muted }
         : s
     ));
@@ -128,6 +135,40 @@ export function usePerAppMixer(pollInterval = 3000): UsePerAppMixerReturn {
     };
   }, [fetchSessions, pollInterval]);
 
+  // Real-time: session created/state changed -> re-query; volume/mute -> patch in place
+  useEffect(() => {
+    let isMounted = true;
+    const unlisteners: Array<() => void> = [];
+
+    listen("audio-sessions-changed", () => {
+      if (isMounted) fetchSessions();
+    }).then((fn) => {
+      if (isMounted) unlisteners.push(fn); else fn();
+    }).catch(() => {});
+
+    listen<{
+      session_id: string;
+      process_id: number;
+      volume: number;
+      is_muted: boolean;
+    }>("audio-session-updated", (event) => {
+      if (!isMounted) return;
+      const u = event.payload;
+      setSessions(prev => prev.map(s =>
+        s.sessionId === u.session_id
+          ? { ...s, volume: u.volume, isMuted: u.is_muted }
+          : s
+      ));
+    }).then((fn) => {
+      if (isMounted) unlisteners.push(fn); else fn();
+    }).catch(() => {});
+
+    return () => {
+      isMounted = false;
+      unlisteners.forEach(fn => fn());
+    };
+  }, [fetchSessions]);
+
   return {
     sessions,
     isLoading,